@@ -1,5 +1,9 @@
 use crate::{
-    arbitrage_engine::{get_price_delta, BasePool, ComputedParams, OptimalAmountResult},
+    arbitrage_engine::{
+        get_price_delta, BasePool, ComputedParams, ExpectedReserves, FlashLoanParams,
+        OptimalAmountResult, PumpswapAccountsMode, SizingMode, TipParams,
+    },
+    commons::MintScreening,
     error::ErrorCode,
 };
 use anchor_lang::prelude::{borsh::BorshDeserialize, *};
@@ -10,8 +14,45 @@ pub struct UniversalOptimalAmountParams {
     pub min_delta_percent: u32,
     pub min_step_size: u64,
     pub min_amount: u64,
+
+    // Подбор суммы: Search - полный поиск, Hint - только в коридоре вокруг оценки бота,
+    // Fixed - одна симуляция. Оценка вне границ пулов прижимается к ним при clamp_sizing, иначе ошибка
+    pub sizing: SizingMode,
+    pub clamp_sizing: bool,
+
+    // Защита от устаревшей котировки: дедлайн по слоту и ожидаемые резервы пулов A и B
+    pub max_slot: Option<u64>,
+    pub reserve_tolerance_bps: u32,
+    pub expected_reserves_a: Option<ExpectedReserves>,
+    pub expected_reserves_b: Option<ExpectedReserves>,
+
+    // Чаевые валидатору от реализованной прибыли, None - не платятся
+    pub tip: Option<TipParams>,
+
+    // Проверка intermediate минта до сделки, None - без проверки
+    pub mint_screening: Option<MintScreening>,
+
+    // Source - нативный SOL: обернуть нужную сумму перед первой ногой и закрыть WSOL ATA после
+    // последней, прибыль считается в лампортах
+    pub wrap_sol: bool,
+
+    // Передача вспомогательных аккаунтов Pumpswap-ног
+    pub pumpswap_accounts: PumpswapAccountsMode,
+
+    // Flash-займ вокруг арбитража: max_amount_in берётся из суммы займа
+    pub flash_loan: Option<FlashLoanParams>,
+
+    // Лимиты капитала, применяются до поиска: вход не меньше min_amount и не больше
+    // max_amount_in и capital_fraction_bps от баланса source (None - DEFAULT_CAPITAL_FRACTION_BPS).
+    // В режиме wrap_sol на кошельке остаётся wsol_reserve_lamports под комиссии и ренту
+    pub max_amount_in: Option<u64>,
+    pub capital_fraction_bps: Option<u16>,
+    pub wsol_reserve_lamports: u64,
 }
 
+// Доля баланса без явного capital_fraction_bps - как прежний поиск от 99% баланса
+pub const DEFAULT_CAPITAL_FRACTION_BPS: u16 = 9_900;
+
 #[derive(Debug)]
 pub struct ProfitResult {
     pub profit: i64,
@@ -19,6 +60,7 @@ pub struct ProfitResult {
     pub consumed_in_amount: u64,
     pub price_delta_bps: u64,
     pub direction_a_to_b: bool,
+    pub crosses_fee_tier: bool,
 }
 
 fn get_profit(
@@ -29,9 +71,6 @@ fn get_profit(
     let in_result = pool_in.get_amount_out(amount_in, false)?;
     let out_result = pool_out.get_amount_out(in_result.amount_out, true)?;
 
-    // msg!("{:?}", in_result);
-    // msg!("{:?}", out_result);
-
     let (price_delta_bps, direction_a_to_b) =
         get_price_delta(in_result.new_price, out_result.new_price);
 
@@ -43,7 +82,7 @@ fn get_profit(
         consumed_in_amount = in_result.consumed_in_amount;
     }
 
-    let profit = (out_result.amount_out as i64) - (consumed_in_amount as i64);
+    let profit = calc_profit(out_result.amount_out, consumed_in_amount)?;
 
     Ok(ProfitResult {
         profit,
@@ -51,9 +90,41 @@ fn get_profit(
         consumed_in_amount,
         price_delta_bps: price_delta_bps.ceil() as u64,
         direction_a_to_b,
+        crosses_fee_tier: in_result.crosses_fee_tier || out_result.crosses_fee_tier,
     })
 }
 
+impl UniversalOptimalAmountParams {
+    /// Максимальный вход из собственного баланса source (лампортов в режиме wrap_sol)
+    pub fn capital_max_amount_in(&self, balance: u64) -> u64 {
+        let available = if self.wrap_sol {
+            balance.saturating_sub(self.wsol_reserve_lamports)
+        } else {
+            balance
+        };
+
+        let fraction_bps = self
+            .capital_fraction_bps
+            .unwrap_or(DEFAULT_CAPITAL_FRACTION_BPS)
+            .min(10_000);
+        let capital = (available as u128 * fraction_bps as u128 / 10_000) as u64;
+
+        self.clamp_max_amount_in(capital)
+    }
+
+    /// Ограничивает вход явным max_amount_in
+    pub fn clamp_max_amount_in(&self, amount: u64) -> u64 {
+        self.max_amount_in
+            .map_or(amount, |max_amount_in| amount.min(max_amount_in))
+    }
+}
+
+pub fn calc_profit(amount_out: u64, amount_in: u64) -> Result<i64> {
+    let profit = amount_out as i128 - amount_in as i128;
+
+    i64::try_from(profit).map_err(|_| ErrorCode::ProfitOverflow.into())
+}
+
 /// Находит оптимальную сумму для арбитража между двумя пулами
 pub fn find_optimal_amount(
     pool_a: &dyn BasePool,
@@ -65,10 +136,14 @@ pub fn find_optimal_amount(
     let price_b = pool_b.get_price()?;
     let (price_delta_bps, direction_a_to_b) = get_price_delta(price_a, price_b);
 
-    let max_iterations = params.max_iterations;
+    // Fixed: только симуляция заданной суммы
+    let max_iterations = if matches!(params.sizing, SizingMode::Fixed { .. }) {
+        0
+    } else {
+        params.max_iterations
+    };
     let min_delta_percent = params.min_delta_percent;
     let min_step_size = params.min_step_size;
-    let min_amount = params.min_amount;
     let max_amount = computed_params.max_amount_in;
     let (pool_in, pool_out): (&dyn BasePool, &dyn BasePool) = if direction_a_to_b {
         (pool_a, pool_b)
@@ -79,24 +154,24 @@ pub fn find_optimal_amount(
     let pool_in_max_amount = pool_in.get_max_amount(price_delta_bps, false)?;
     let pool_out_max_amount = pool_out.get_max_amount(price_delta_bps, true)?;
 
-    // msg!(
-    //     "price_delta_bps={}, direction_a_to_b={}, pool_out_max_amount={}",
-    //     price_delta_bps,
-    //     direction_a_to_b,
-    //     pool_out_max_amount
-    // );
-
     let min_pool_max_amount = pool_in_max_amount.min(pool_out_max_amount);
     let max_amount = max_amount.min(min_pool_max_amount);
 
+    let bounds = params
+        .sizing
+        .bounds(params.min_amount, max_amount, params.clamp_sizing)?;
+    let min_amount = bounds.min_amount;
+    let max_amount = bounds.max_amount;
+
     let mut current_amount = max_amount;
-    let mut step = max_amount / 2;
+    let mut step = bounds.step;
 
     let initial_result = get_profit(pool_in, pool_out, current_amount)?;
     let mut best_profit = initial_result.profit;
     let mut best_in_amount = initial_result.consumed_in_amount;
     let mut best_intermediate_amount = initial_result.intermediate_output;
     let mut best_price_delta_bps = initial_result.price_delta_bps;
+    let mut best_crosses_fee_tier = initial_result.crosses_fee_tier;
 
     if best_in_amount == 0 {
         return Err(ErrorCode::NoArbitrageOpportunity.into());
@@ -104,19 +179,10 @@ pub fn find_optimal_amount(
 
     let mut prev_profit = best_profit;
     let mut direction = -1i8;
+    let mut iterations = 0u32;
 
-    // msg!(
-    //     "Iteration -: amount={} SOL, profit={} SOL, step={} SOL, priceDelta={}, direction_a_to_b={}",
-    //     best_in_amount,
-    //     best_profit,
-    //     step,
-    //     initial_result.price_delta_bps,
-    //     initial_result.direction_a_to_b,
-    // );
-    // msg!("Iteration -: {:?}", initial_result);
-
-    // let mut was_equal_found = false;
     for i in 0..max_iterations {
+        iterations = i + 1;
         let is_negative_profit = prev_profit < 0;
         let step_multiplier = if is_negative_profit {
             step + step / 2
@@ -132,10 +198,10 @@ pub fn find_optimal_amount(
                 current_amount = min_amount;
             }
         } else {
-            current_amount += step_multiplier;
+            current_amount = current_amount.saturating_add(step_multiplier);
         }
 
-        step = step / step_divisor;
+        step /= step_divisor;
 
         let current_result = get_profit(pool_in, pool_out, current_amount)?;
         let current_profit = current_result.profit;
@@ -147,47 +213,24 @@ pub fn find_optimal_amount(
             best_profit = current_profit;
             best_in_amount = current_in_amount;
             best_intermediate_amount = current_intermediate;
+            best_crosses_fee_tier = current_result.crosses_fee_tier;
         }
 
-        let mut profit_delta = 0i64;
+        let mut profit_delta = 0i128;
         if prev_profit != 0 {
-            let profit_change = current_profit - prev_profit;
-            profit_delta = profit_change * 100 / prev_profit;
+            let profit_change = current_profit as i128 - prev_profit as i128;
+            profit_delta = profit_change * 100 / prev_profit as i128;
         }
 
         if current_profit < prev_profit {
             direction *= -1;
         }
 
-        // msg!(
-        //     "Iteration {}: amount={} SOL, profit={} SOL, best_profit={} SOL, step={} SOL, delta={}%, priceDelta={}, direction_a_to_b={}",
-        //     i,
-        //     current_in_amount,
-        //     current_profit,
-        //     best_profit,
-        //     step,
-        //     profit_delta,
-        //     current_result.price_delta_bps,
-        //     current_result.direction_a_to_b
-        // );
-
-        // msg!("Iteration {}: {:?}", i, current_result);
-
         if current_price_delta_bps < best_price_delta_bps {
             best_price_delta_bps = current_price_delta_bps;
-            // was_equal_found = false;
-        } else if current_price_delta_bps == best_price_delta_bps {
-            // if was_equal_found {
-            //     msg!("Early exit: another equal in a row");
-            //     break;
-            // }
-            // was_equal_found = true;
-        } else {
-            // msg!("Early exit: price delta become worse");
-            // break;
         }
 
-        if profit_delta.abs() < min_delta_percent as i64 && i > 0 {
+        if profit_delta.abs() < min_delta_percent as i128 && i > 0 {
             msg!("Early exit: small profit delta");
             break;
         }
@@ -210,13 +253,118 @@ pub fn find_optimal_amount(
         intermediate_amount: best_intermediate_amount,
         direction_a_to_b,
         profit: best_profit,
+        price_delta_bps: price_delta_bps.ceil() as u64,
+        iterations,
+        crosses_fee_tier: best_crosses_fee_tier,
     };
 
-    msg!("{:?}", res);
+    // Чаевые монотонны по прибыли, а комиссия займа фиксирована, поэтому максимум прибыли
+    // остаётся максимумом и после их вычета. Чаевые и прибыль в лампортах: режим tip
+    // допускается только для SOL source (TipParams::check_source в execute_arb)
+    let expected_tip = params
+        .tip
+        .map(|tip| tip.calc_tip(res.profit.max(0) as u64))
+        .unwrap_or(0);
 
-    if res.profit <= 0 {
+    let min_profit = expected_tip.saturating_add(computed_params.loan_fee);
+
+    if res.profit <= 0 || res.profit as u64 <= min_profit {
         return Err(ErrorCode::NoArbitrageOpportunity.into());
     }
 
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests_capital_limits {
+    use super::*;
+
+    fn params() -> UniversalOptimalAmountParams {
+        UniversalOptimalAmountParams {
+            max_iterations: 10,
+            min_delta_percent: 0,
+            min_step_size: 0,
+            min_amount: 0,
+            max_amount_in: None,
+            capital_fraction_bps: None,
+            wsol_reserve_lamports: 0,
+            sizing: SizingMode::Search,
+            clamp_sizing: false,
+            max_slot: None,
+            reserve_tolerance_bps: 0,
+            expected_reserves_a: None,
+            expected_reserves_b: None,
+            tip: None,
+            mint_screening: None,
+            wrap_sol: false,
+            pumpswap_accounts: PumpswapAccountsMode::Passed,
+            flash_loan: None,
+        }
+    }
+
+    #[test]
+    fn test_default_fraction() {
+        assert_eq!(params().capital_max_amount_in(1_000), 990);
+        assert_eq!(params().capital_max_amount_in(0), 0);
+
+        let mut params = params();
+        params.max_amount_in = Some(500);
+        assert_eq!(params.capital_max_amount_in(1_000), 500);
+    }
+
+    #[test]
+    fn test_capital_fraction_and_max() {
+        let mut params = params();
+        params.capital_fraction_bps = Some(2_500);
+        assert_eq!(params.capital_max_amount_in(1_000_000), 250_000);
+
+        params.max_amount_in = Some(100_000);
+        assert_eq!(params.capital_max_amount_in(1_000_000), 100_000);
+        assert_eq!(params.capital_max_amount_in(200_000), 50_000);
+
+        // больше 100% не бывает
+        params.capital_fraction_bps = Some(20_000);
+        assert_eq!(params.capital_max_amount_in(50_000), 50_000);
+    }
+
+    #[test]
+    fn test_wsol_reserve() {
+        let mut params = params();
+        params.wsol_reserve_lamports = 10_000_000;
+        params.capital_fraction_bps = Some(10_000);
+        // резерв учитывается только в режиме wrap_sol
+        assert_eq!(params.capital_max_amount_in(1_000_000_000), 1_000_000_000);
+
+        params.wrap_sol = true;
+        assert_eq!(params.capital_max_amount_in(1_000_000_000), 990_000_000);
+        assert_eq!(params.capital_max_amount_in(5_000_000), 0);
+
+        params.capital_fraction_bps = Some(5_000);
+        assert_eq!(params.capital_max_amount_in(1_010_000_000), 500_000_000);
+    }
+}
+
+#[cfg(test)]
+mod tests_calc_profit {
+    use super::*;
+
+    #[test]
+    fn test_calc_profit() {
+        assert_eq!(calc_profit(110, 100).unwrap(), 10);
+        assert_eq!(calc_profit(100, 110).unwrap(), -10);
+        assert_eq!(calc_profit(i64::MAX as u64, 0).unwrap(), i64::MAX);
+        assert_eq!(calc_profit(0, 1u64 << 63).unwrap(), i64::MIN);
+    }
+
+    #[test]
+    fn test_calc_profit_overflow() {
+        assert_eq!(
+            calc_profit(u64::MAX, 0).unwrap_err(),
+            ErrorCode::ProfitOverflow.into()
+        );
+        assert_eq!(
+            calc_profit(0, u64::MAX).unwrap_err(),
+            ErrorCode::ProfitOverflow.into()
+        );
+    }
+}
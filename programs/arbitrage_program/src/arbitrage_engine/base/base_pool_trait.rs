@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use super::{LiquidityType, SwapResult};
+use super::{LiquidityType, PoolKind, SwapResult};
 
 pub trait BasePool {
     fn get_fee_rate_f64(&self) -> f64;
@@ -8,11 +8,21 @@ pub trait BasePool {
     fn get_amount_out(&self, amount_in: u64, swap_for_quote: bool) -> Result<SwapResult>;
     fn get_amount_in(&self, amount_out: u64, swap_for_quote: bool) -> Result<SwapResult>;
     fn get_pool_type(&self) -> Result<LiquidityType>;
+    fn get_pool_kind(&self) -> PoolKind;
+    fn get_pool_key(&self) -> Pubkey;
 
     fn get_max_amount(&self, price_delta: f64, swap_for_quote: bool) -> Result<u64>;
 
     fn get_base_quote_product(&self) -> Result<u128>;
 
+    /// Резервы (base, quote), по которым пул считает цену
+    fn get_reserves(&self) -> Result<(u64, u64)>;
+
     fn swap(&self, amount_in: u64, min_amount_out: u64, source_to_intermediate: bool)
         -> Result<()>;
+
+    /// Первая нога арбитража: source -> intermediate на amount_in, ожидается intermediate_amount
+    fn swap_first_leg(&self, amount_in: u64, _intermediate_amount: u64) -> Result<()> {
+        self.swap(amount_in, 0, true)
+    }
 }
@@ -1,11 +1,14 @@
+use crate::arbitrage_engine::{BasePool, ExpectedReserves};
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
 pub fn get_price_delta(price_a: f64, price_b: f64) -> (f64, bool) {
     let direction_a_to_b = price_a < price_b;
     let delta = (price_a - price_b).abs();
     let min_price = if direction_a_to_b { price_a } else { price_b };
     let bps = (delta / min_price) * 10000.0;
-    let data = (bps, direction_a_to_b);
 
-    return data;
+    (bps, direction_a_to_b)
 }
 
 pub fn get_min_price_delta_bps(f_a: f64, f_b: f64) -> f64 {
@@ -14,8 +17,43 @@ pub fn get_min_price_delta_bps(f_a: f64, f_b: f64) -> f64 {
 
     let denominator = one_minus_f_a * one_minus_f_b;
 
-    let bps_min = ((1.0 / denominator) - 1.0) * 10_000.0;
-    bps_min
+    ((1.0 / denominator) - 1.0) * 10_000.0
+}
+
+/// Проверяет, что слот не превысил дедлайн котировки
+pub fn check_quote_deadline(current_slot: u64, max_slot: Option<u64>) -> Result<()> {
+    if let Some(max_slot) = max_slot {
+        require!(current_slot <= max_slot, ErrorCode::QuoteExpired);
+    }
+
+    Ok(())
+}
+
+/// Проверяет, что резервы пула отклонились от ожидаемых не более чем на tolerance_bps
+pub fn check_reserves_drift(
+    pool: &dyn BasePool,
+    expected: Option<ExpectedReserves>,
+    tolerance_bps: u32,
+) -> Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    let (base_reserve, quote_reserve) = pool.get_reserves()?;
+
+    require!(
+        is_within_tolerance(expected.base_reserve, base_reserve, tolerance_bps)
+            && is_within_tolerance(expected.quote_reserve, quote_reserve, tolerance_bps),
+        ErrorCode::ReserveDriftExceeded
+    );
+
+    Ok(())
+}
+
+pub fn is_within_tolerance(expected: u64, actual: u64, tolerance_bps: u32) -> bool {
+    let drift = expected.abs_diff(actual) as u128;
+
+    drift * 10_000 <= expected as u128 * tolerance_bps as u128
 }
 
 #[cfg(test)]
@@ -35,4 +73,29 @@ mod tests {
 
         assert_eq!(bps, 230.67688759184745)
     }
+
+    #[test]
+    fn test_check_quote_deadline() {
+        assert!(check_quote_deadline(100, None).is_ok());
+        assert!(check_quote_deadline(100, Some(100)).is_ok());
+        assert_eq!(
+            check_quote_deadline(101, Some(100)).unwrap_err(),
+            ErrorCode::QuoteExpired.into()
+        );
+    }
+
+    #[test]
+    fn test_is_within_tolerance() {
+        assert!(is_within_tolerance(10_000, 10_000, 0));
+        assert!(!is_within_tolerance(10_000, 10_001, 0));
+
+        // 50 bps = 0.5%
+        assert!(is_within_tolerance(10_000, 10_050, 50));
+        assert!(is_within_tolerance(10_000, 9_950, 50));
+        assert!(!is_within_tolerance(10_000, 10_051, 50));
+        assert!(!is_within_tolerance(10_000, 9_949, 50));
+
+        assert!(is_within_tolerance(u64::MAX, u64::MAX - 1, 1));
+        assert!(!is_within_tolerance(0, 1, u32::MAX));
+    }
 }
@@ -1,16 +1,48 @@
+use crate::commons::MintScreening;
+use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum LiquidityType {
     Concentrated,
     Constant,
 }
+/// DEX, к которому относится пул
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolKind {
+    Pumpswap,
+    RaydiumAmmV4,
+}
+
+/// Как программа получает вспомогательные аккаунты Pumpswap (dex/pumpswap/pdas.rs).
+/// Borsh-раскладка совпадает с прежним флагом verify_pumpswap_pdas: false = Passed, true = Verified
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PumpswapAccountsMode {
+    /// Полный PumpswapAccounts передаётся как есть, проверяет сам Pumpswap при CPI
+    Passed,
+    /// Полный PumpswapAccounts, выведенные PDA сверяются с переданными (~4.5k CU на пул)
+    Verified,
+    /// Ноги two_pool_arb передаются как PumpswapSlimAccounts, выведенные аккаунты ищутся
+    /// по ключу в хвосте remaining_accounts. Для именованных PumpswapAccounts равен Verified
+    Derived,
+}
+
+impl PumpswapAccountsMode {
+    /// Сверять ли полный PumpswapAccounts с выведенными PDA
+    pub fn verifies_passed(&self) -> bool {
+        *self != PumpswapAccountsMode::Passed
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct SwapResult {
     pub amount_out: u64,
     pub consumed_in_amount: u64,
     pub is_fully_filled: bool,
     pub new_price: f64,
+    // После сделки пул попадает в другой тир комиссий (Pumpswap), следующая котировка будет по нему
+    pub crosses_fee_tier: bool,
 }
 
 /// Структура для возврата результата поиска оптимальной суммы
@@ -20,12 +52,19 @@ pub struct OptimalAmountResult {
     pub intermediate_amount: u64,
     pub direction_a_to_b: bool,
     pub profit: i64,
+    // Разница цен пулов до сделки и число итераций поиска
+    pub price_delta_bps: u64,
+    pub iterations: u32,
+    // Одна из ног лучшей суммы переводит пул в другой тир комиссий
+    pub crosses_fee_tier: bool,
 }
 
 /// Структура для передачи вычисленных параметров в функции подбора цены
 #[derive(Debug, Clone, Copy)]
 pub struct ComputedParams {
     pub max_amount_in: u64,
+    // Фиксированная стоимость сделки (комиссия flash-займа), вычитается из прибыли
+    pub loan_fee: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -33,4 +72,290 @@ pub struct BaseSwapParams {
     pub amount_in: u64,
     pub min_amount_out: u64,
     pub source_to_intermediate: bool,
+
+    // Проверка intermediate минта перед покупкой, None - без проверки
+    pub mint_screening: Option<MintScreening>,
+
+    // Обернуть SOL в WSOL ATA перед свопом и закрыть его после
+    pub wrap_sol: bool,
+
+    // Передача вспомогательных аккаунтов Pumpswap
+    pub pumpswap_accounts: PumpswapAccountsMode,
+}
+
+/// Резервы пула (base, quote), на которых бот построил котировку off-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct ExpectedReserves {
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+}
+
+/// Чаевые валидатору пропорционально прибыли: max(min_tip, profit * tip_bps / 10000) лампортов
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct TipParams {
+    pub min_tip: u64,
+    pub tip_bps: u16,
+}
+
+impl TipParams {
+    /// Чаевые в лампортах считаются от прибыли, поэтому прибыль тоже должна быть в SOL
+    pub fn check_source(source_mint: &Pubkey) -> Result<()> {
+        require_keys_eq!(
+            *source_mint,
+            spl_token::native_mint::ID,
+            ErrorCode::TipRequiresNativeSource
+        );
+
+        Ok(())
+    }
+
+    pub fn calc_tip(&self, profit: u64) -> u64 {
+        let proportional = (profit as u128 * self.tip_bps as u128 / 10_000) as u64;
+
+        proportional.max(self.min_tip)
+    }
+}
+
+/// Подбор суммы арбитража: полный поиск, поиск в узком коридоре вокруг off-chain оценки
+/// или одна симуляция заданной суммы
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizingMode {
+    Search,
+    Hint { amount: u64, bracket_bps: u32 },
+    Fixed { amount: u64 },
+}
+
+/// Границы поиска суммы: нижняя, верхняя и начальный шаг
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizingBounds {
+    pub min_amount: u64,
+    pub max_amount: u64,
+    pub step: u64,
+}
+
+impl SizingMode {
+    /// Границы поиска внутри [min_amount, max_amount], где max_amount уже ограничен пулами.
+    /// Подсказка вне границ прижимается к ним при clamp, иначе - ошибка
+    pub fn bounds(&self, min_amount: u64, max_amount: u64, clamp: bool) -> Result<SizingBounds> {
+        let fit = |amount: u64| -> Result<u64> {
+            if (min_amount..=max_amount).contains(&amount) {
+                Ok(amount)
+            } else if clamp {
+                Ok(amount.clamp(min_amount.min(max_amount), max_amount))
+            } else {
+                Err(ErrorCode::SizingHintOutOfBounds.into())
+            }
+        };
+
+        match *self {
+            SizingMode::Search => Ok(SizingBounds {
+                min_amount,
+                max_amount,
+                step: max_amount / 2,
+            }),
+            SizingMode::Hint {
+                amount,
+                bracket_bps,
+            } => {
+                let amount = fit(amount)?;
+                let bracket = (amount as u128 * bracket_bps as u128 / 10_000) as u64;
+                let low = amount.saturating_sub(bracket).max(min_amount);
+                let high = amount.saturating_add(bracket).min(max_amount);
+
+                Ok(SizingBounds {
+                    min_amount: low.min(high),
+                    max_amount: high,
+                    step: high.saturating_sub(low) / 2,
+                })
+            }
+            SizingMode::Fixed { amount } => {
+                let amount = fit(amount)?;
+
+                Ok(SizingBounds {
+                    min_amount: amount,
+                    max_amount: amount,
+                    step: 0,
+                })
+            }
+        }
+    }
+}
+
+/// Режим flash-займа: комиссия кредитора в bps от занятой суммы
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct FlashLoanParams {
+    pub fee_bps: u16,
+}
+
+impl FlashLoanParams {
+    /// Комиссия задаётся вызывающим и не проверяется кредитором до repay, поэтому не ниже минимума из Config
+    pub fn check_fee(&self, min_fee_bps: u16) -> Result<()> {
+        require!(
+            self.fee_bps >= min_fee_bps,
+            ErrorCode::FlashLoanFeeBelowMinimum
+        );
+
+        Ok(())
+    }
+
+    /// Комиссия округляется вверх, как у кредитора
+    pub fn calc_fee(&self, borrowed: u64) -> u64 {
+        (borrowed as u128 * self.fee_bps as u128).div_ceil(10_000) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests_pumpswap_accounts_mode {
+    use super::*;
+
+    #[test]
+    fn test_borsh_matches_bool_flag() {
+        let mode =
+            |flag: bool| PumpswapAccountsMode::try_from_slice(&borsh::to_vec(&flag).unwrap());
+
+        assert_eq!(mode(false).unwrap(), PumpswapAccountsMode::Passed);
+        assert_eq!(mode(true).unwrap(), PumpswapAccountsMode::Verified);
+        assert!(!PumpswapAccountsMode::Passed.verifies_passed());
+        assert!(PumpswapAccountsMode::Derived.verifies_passed());
+    }
+}
+
+#[cfg(test)]
+mod tests_tip_params {
+    use super::*;
+
+    #[test]
+    fn test_calc_tip() {
+        let tip = TipParams {
+            min_tip: 1_000,
+            tip_bps: 5_000,
+        };
+
+        // ниже порога платится min_tip
+        assert_eq!(tip.calc_tip(0), 1_000);
+        assert_eq!(tip.calc_tip(1_999), 1_000);
+        // выше - доля прибыли
+        assert_eq!(tip.calc_tip(1_000_000), 500_000);
+        assert_eq!(tip.calc_tip(u64::MAX), u64::MAX / 2);
+    }
+
+    #[test]
+    fn test_check_source() {
+        assert!(TipParams::check_source(&spl_token::native_mint::ID).is_ok());
+        // прибыль в другом токене нельзя сравнивать с чаевыми в лампортах
+        assert_eq!(
+            TipParams::check_source(&Pubkey::new_unique()).unwrap_err(),
+            ErrorCode::TipRequiresNativeSource.into()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_sizing_mode {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        assert_eq!(
+            SizingMode::Search.bounds(10, 1_000, false).unwrap(),
+            SizingBounds {
+                min_amount: 10,
+                max_amount: 1_000,
+                step: 500,
+            }
+        );
+    }
+
+    #[test]
+    fn test_hint_bracket() {
+        let hint = SizingMode::Hint {
+            amount: 1_000,
+            bracket_bps: 1_000,
+        };
+
+        assert_eq!(
+            hint.bounds(0, 10_000, false).unwrap(),
+            SizingBounds {
+                min_amount: 900,
+                max_amount: 1_100,
+                step: 100,
+            }
+        );
+        // коридор обрезается границами пула
+        assert_eq!(
+            hint.bounds(950, 1_050, false).unwrap(),
+            SizingBounds {
+                min_amount: 950,
+                max_amount: 1_050,
+                step: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn test_hint_out_of_bounds() {
+        let hint = SizingMode::Hint {
+            amount: 5_000,
+            bracket_bps: 1_000,
+        };
+
+        assert_eq!(
+            hint.bounds(0, 1_000, false).unwrap_err(),
+            ErrorCode::SizingHintOutOfBounds.into()
+        );
+        // при clamp коридор строится вокруг максимума
+        assert_eq!(
+            hint.bounds(0, 1_000, true).unwrap(),
+            SizingBounds {
+                min_amount: 900,
+                max_amount: 1_000,
+                step: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fixed() {
+        let fixed = SizingMode::Fixed { amount: 500 };
+
+        assert_eq!(
+            fixed.bounds(0, 1_000, false).unwrap(),
+            SizingBounds {
+                min_amount: 500,
+                max_amount: 500,
+                step: 0,
+            }
+        );
+        assert_eq!(
+            fixed.bounds(0, 100, false).unwrap_err(),
+            ErrorCode::SizingHintOutOfBounds.into()
+        );
+        assert_eq!(fixed.bounds(0, 100, true).unwrap().max_amount, 100);
+        assert_eq!(fixed.bounds(600, 1_000, true).unwrap().min_amount, 600);
+    }
+}
+
+#[cfg(test)]
+mod tests_flash_loan_params {
+    use super::*;
+
+    #[test]
+    fn test_calc_fee() {
+        let params = FlashLoanParams { fee_bps: 9 };
+
+        assert_eq!(params.calc_fee(0), 0);
+        assert_eq!(params.calc_fee(1), 1);
+        assert_eq!(params.calc_fee(100_000_000_000), 90_000_000);
+        assert_eq!(FlashLoanParams { fee_bps: 0 }.calc_fee(1_000), 0);
+    }
+
+    #[test]
+    fn test_check_fee() {
+        assert!(FlashLoanParams { fee_bps: 9 }.check_fee(9).is_ok());
+        assert!(FlashLoanParams { fee_bps: 0 }.check_fee(0).is_ok());
+        assert_eq!(
+            FlashLoanParams { fee_bps: 8 }.check_fee(9).unwrap_err(),
+            ErrorCode::FlashLoanFeeBelowMinimum.into()
+        );
+    }
 }
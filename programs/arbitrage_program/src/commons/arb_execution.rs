@@ -0,0 +1,236 @@
+use crate::arbitrage_engine::{
+    calc_profit, check_reserves_drift, find_optimal_amount, BasePool, ComputedParams,
+    OptimalAmountResult, TipParams, UniversalOptimalAmountParams,
+};
+use crate::commons::{
+    create_ata_if_missing, distribute_profit, find_flash_loan, get_ata_balance, get_native_balance,
+    pay_tip, resolve_token_program, unwrap_sol, wrap_sol, ProfitDestinations, SwapAuthority,
+};
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Аккаунты арбитражной инструкции, не зависящие от DEX ног
+pub struct ArbAccounts<'a, 'info> {
+    pub common: &'a CommonAccounts<'info>,
+    pub config: &'a Config,
+    pub risk_state: &'a mut RiskState,
+    pub pair_stats: Option<&'a mut PairStats>,
+    pub authority: SwapAuthority<'a, 'info>,
+    pub profit_destinations: ProfitDestinations<'a, 'info>,
+    pub validator_tip_account: Option<&'a AccountInfo<'info>>,
+    pub tip_unwrap_token_account: Option<&'a AccountInfo<'info>>,
+    pub instructions_sysvar: Option<&'a AccountInfo<'info>>,
+}
+
+impl<'a, 'info> ArbAccounts<'a, 'info> {
+    /// Определяет подписанта CPI и проверяет, что risk_state и operator_token_account
+    /// принадлежат подписанту инструкции
+    pub fn new(
+        common: &'a CommonAccounts<'info>,
+        shared: &'a mut ArbSharedAccounts<'info>,
+    ) -> Result<Self> {
+        let ArbSharedAccounts {
+            config,
+            risk_state,
+            pair_stats,
+            vault_signer,
+            treasury_token_account,
+            operator_token_account,
+            tip_account,
+            validator_tip_account,
+            tip_unwrap_token_account,
+            instructions_sysvar,
+        } = shared;
+
+        require_keys_eq!(
+            risk_state.operator,
+            common.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        if let Some(operator_token_account) = operator_token_account {
+            require!(
+                operator_token_account.mint == common.user_source_token_mint.key()
+                    && operator_token_account.owner == common.user.key(),
+                ErrorCode::InvalidProfitSplitAccount
+            );
+        }
+
+        Ok(Self {
+            common,
+            config,
+            risk_state,
+            pair_stats: pair_stats.as_deref_mut(),
+            authority: SwapAuthority::resolve(common, vault_signer)?,
+            profit_destinations: ProfitDestinations {
+                treasury_token_account: treasury_token_account.as_deref(),
+                operator_token_account: operator_token_account
+                    .as_ref()
+                    .map(|account| account.as_ref()),
+                tip_account: tip_account.as_deref(),
+            },
+            validator_tip_account: validator_tip_account.as_deref(),
+            tip_unwrap_token_account: tip_unwrap_token_account.as_deref(),
+            instructions_sysvar: instructions_sysvar.as_deref(),
+        })
+    }
+}
+
+/// Результат исполненного арбитража для событий и статистики
+pub struct ArbExecution {
+    pub result: OptimalAmountResult,
+    // Изменение баланса source (или лампортов) за вычетом комиссии займа
+    pub realized_profit: i64,
+}
+
+/// Общая часть арбитражных инструкций: лимиты риска, сайзинг, две ноги свопа,
+/// учёт реализованной прибыли, статистика пары, чаевые и распределение прибыли
+pub fn execute_arb<'info>(
+    mut accounts: ArbAccounts<'_, 'info>,
+    pool_a: &dyn BasePool,
+    pool_b: &dyn BasePool,
+    params: &UniversalOptimalAmountParams,
+    slot: u64,
+) -> Result<ArbExecution> {
+    let common = accounts.common;
+    let authority = &accounts.authority;
+
+    if let Some(pair_stats) = &accounts.pair_stats {
+        pair_stats.check_pair(
+            &pool_a.get_pool_key(),
+            &pool_b.get_pool_key(),
+            common.user.key,
+        )?;
+    }
+
+    // Лимиты риска оператора: cool-down, убыток и бюджет окна
+    let risk_limits = accounts.config.risk_limits;
+    let risk_max_amount_in = accounts.risk_state.check_pre_trade(&risk_limits, slot)?;
+
+    if params.tip.is_some() {
+        TipParams::check_source(common.user_source_token_mint.key)?;
+    }
+
+    // Рынок ушёл от котировки - выходим до любых CPI
+    check_reserves_drift(
+        pool_a,
+        params.expected_reserves_a,
+        params.reserve_tolerance_bps,
+    )?;
+    check_reserves_drift(
+        pool_b,
+        params.expected_reserves_b,
+        params.reserve_tolerance_bps,
+    )?;
+
+    // В режиме wrap_sol баланс - лампорты пользователя вместе с WSOL ATA
+    let start_balance = if params.wrap_sol {
+        get_native_balance(common)
+    } else {
+        get_ata_balance(&common.user_source_token_account)?
+    };
+
+    // В режиме flash-займа вход ограничен занятой суммой, комиссия займа вычитается из прибыли.
+    // Без займа - лимитами капитала из params
+    let (max_amount_in, loan_fee) = match &params.flash_loan {
+        Some(flash_loan_params) => {
+            flash_loan_params.check_fee(accounts.config.min_flash_loan_fee_bps)?;
+
+            let instructions_sysvar = accounts
+                .instructions_sysvar
+                .ok_or(ErrorCode::FlashLoanSysvarMissing)?;
+            let flash_loan =
+                find_flash_loan(instructions_sysvar, common.user_source_token_account.key)?;
+
+            (
+                params.clamp_max_amount_in(flash_loan.amount),
+                flash_loan_params.calc_fee(flash_loan.amount),
+            )
+        }
+        None => (params.capital_max_amount_in(start_balance), 0),
+    };
+    let computed_params = ComputedParams {
+        max_amount_in: max_amount_in.min(risk_max_amount_in),
+        loan_fee,
+    };
+    require!(
+        computed_params.max_amount_in >= params.min_amount.max(1),
+        ErrorCode::CapitalBelowMinAmountIn
+    );
+
+    let result = find_optimal_amount(pool_a, pool_b, params, &computed_params)?;
+
+    // Создаем ATA, если не существует
+    create_ata_if_missing(
+        &common.user,
+        &authority.to_account_info(),
+        &common.user_intermediate_token_account,
+        &common.user_intermediate_token_mint,
+        &common.system_program,
+        &resolve_token_program(&common.user_intermediate_token_mint, common)?,
+        &common.associated_token_program,
+    )?;
+
+    if params.wrap_sol {
+        wrap_sol(common, authority, result.amount_in)?;
+    }
+
+    let (pool_in, pool_out) = if result.direction_a_to_b {
+        (pool_a, pool_b)
+    } else {
+        (pool_b, pool_a)
+    };
+    pool_in.swap_first_leg(result.amount_in, result.intermediate_amount)?;
+    pool_out.swap(result.intermediate_amount, 0, false)?;
+
+    // Реализованная прибыль - по факту изменения баланса source токена (или лампортов)
+    let end_balance = if params.wrap_sol {
+        unwrap_sol(common)?;
+        common.user.lamports()
+    } else {
+        get_ata_balance(&common.user_source_token_account)?
+    };
+    let mut profit = end_balance
+        .saturating_sub(start_balance)
+        .saturating_sub(loan_fee);
+    let realized_profit = calc_profit(end_balance, start_balance)?.saturating_sub(loan_fee as i64);
+
+    accounts
+        .risk_state
+        .record_trade(&risk_limits, slot, result.amount_in, realized_profit);
+
+    if let Some(pair_stats) = accounts.pair_stats.as_mut() {
+        pair_stats.record(slot, result.amount_in, realized_profit);
+    }
+
+    if let Some(tip_params) = &params.tip {
+        let validator_tip_account = accounts
+            .validator_tip_account
+            .ok_or(ErrorCode::MissingTipAccount)?;
+
+        profit = pay_tip(
+            tip_params,
+            profit,
+            common,
+            authority,
+            validator_tip_account,
+            accounts.tip_unwrap_token_account,
+            params.wrap_sol,
+        )?;
+    }
+
+    distribute_profit(
+        &accounts.config.profit_split,
+        profit,
+        common,
+        authority,
+        &accounts.profit_destinations,
+        params.wrap_sol,
+    )?;
+
+    Ok(ArbExecution {
+        result,
+        realized_profit,
+    })
+}
@@ -1,17 +1,60 @@
-use crate::commons::get_ata_balance;
-use crate::state::arb_context_accounts::InitArbitrageContextAccounts;
+use crate::commons::{get_ata_balance, has_paired_context_instruction};
+use crate::error::ErrorCode;
+use crate::state::arb_context_accounts::{
+    AssetSnapshot, InitArbitrageContextAccounts, InitArbitrageContextParams, ARB_CTX_VERSION,
+    MAX_CTX_ASSETS,
+};
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+pub fn init_arbitrage_context<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitArbitrageContextAccounts<'info>>,
+    params: InitArbitrageContextParams,
+) -> Result<()> {
+    // В этой же транзакции после init должен идти verify для того же user и arb_ctx
+    require!(
+        has_paired_context_instruction(
+            &ctx.accounts.instructions_sysvar,
+            crate::instruction::VerifyArbitrageContext::DISCRIMINATOR,
+            ctx.accounts.user.key,
+            &ctx.accounts.arb_ctx.key(),
+            true,
+        )?,
+        ErrorCode::MissingVerifyInstruction
+    );
+
+    let asset_accounts = ctx.remaining_accounts;
+
+    require!(
+        asset_accounts.len() <= MAX_CTX_ASSETS,
+        ErrorCode::ContextAssetLimitExceeded
+    );
+    require!(
+        asset_accounts.len() == params.asset_rules.len(),
+        ErrorCode::ContextAssetMismatch
+    );
 
-pub fn init_arbitrage_context(ctx: Context<InitArbitrageContextAccounts>) -> Result<()> {
     let arb_ctx = &mut ctx.accounts.arb_ctx;
     let user = &ctx.accounts.user;
     let user_source_token_account = &ctx.accounts.user_source_token_account;
 
+    arb_ctx.version = ARB_CTX_VERSION;
     arb_ctx.bump = ctx.bumps.arb_ctx;
     arb_ctx.owner = user.key();
 
     arb_ctx.start_sol = user.lamports();
     arb_ctx.start_src = get_ata_balance(user_source_token_account)?;
 
+    // Снапшот дополнительных токен-аккаунтов в порядке remaining_accounts
+    let mut assets = Vec::with_capacity(asset_accounts.len());
+    for (token_account, rule) in asset_accounts.iter().zip(params.asset_rules) {
+        assets.push(AssetSnapshot {
+            token_account: token_account.key(),
+            start_amount: get_ata_balance(token_account)?,
+            rule,
+        });
+    }
+    arb_ctx.assets = assets;
+
     Ok(())
 }
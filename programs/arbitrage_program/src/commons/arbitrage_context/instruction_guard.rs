@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+
+// позиции аккаунтов в init_arbitrage_context / verify_arbitrage_context
+pub const CTX_IX_USER_INDEX: usize = 1;
+pub const CTX_IX_ARB_CTX_INDEX: usize = 3;
+
+/// Проверяет, что ближайшая init/verify инструкция этой программы для того же user и arb_ctx
+/// имеет указанный дискриминатор. `search_after = true` - ближайшая после текущей, иначе - перед ней.
+/// Так init -> arb -> init -> verify не проходит: второй init перезаписал бы снапшот после потери
+pub fn has_paired_context_instruction(
+    instructions_sysvar: &AccountInfo,
+    discriminator: &[u8],
+    user: &Pubkey,
+    arb_ctx: &Pubkey,
+    search_after: bool,
+) -> Result<bool> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+
+    let mut index = current_index;
+    loop {
+        index = if search_after {
+            index + 1
+        } else {
+            let Some(prev) = index.checked_sub(1) else {
+                return Ok(false);
+            };
+            prev
+        };
+
+        // за пределами транзакции load_instruction_at_checked возвращает ошибку
+        let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) else {
+            return Ok(false);
+        };
+
+        if ix.program_id != crate::ID || !is_context_instruction(&ix.data) {
+            continue;
+        }
+
+        let same_user = ix
+            .accounts
+            .get(CTX_IX_USER_INDEX)
+            .is_some_and(|meta| meta.pubkey == *user);
+        let same_ctx = ix
+            .accounts
+            .get(CTX_IX_ARB_CTX_INDEX)
+            .is_some_and(|meta| meta.pubkey == *arb_ctx);
+
+        if same_user && same_ctx {
+            return Ok(ix.data.starts_with(discriminator));
+        }
+    }
+}
+
+fn is_context_instruction(data: &[u8]) -> bool {
+    data.starts_with(crate::instruction::InitArbitrageContext::DISCRIMINATOR)
+        || data.starts_with(crate::instruction::VerifyArbitrageContext::DISCRIMINATOR)
+}
+
+#[cfg(test)]
+mod tests_instruction_guard {
+    use super::*;
+    use anchor_lang::solana_program::sysvar::instructions::{
+        construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction,
+        ID as INSTRUCTIONS_SYSVAR_ID,
+    };
+
+    const INIT: &[u8] = crate::instruction::InitArbitrageContext::DISCRIMINATOR;
+    const VERIFY: &[u8] = crate::instruction::VerifyArbitrageContext::DISCRIMINATOR;
+
+    /// Проверяет пару для каждой init/verify в транзакции из (дискриминатор, arb_ctx)
+    fn check_pairs(transaction: &[(&[u8], Pubkey)], user: &Pubkey) -> Vec<bool> {
+        let filler = Pubkey::new_unique();
+        let keys = transaction
+            .iter()
+            .map(|(_, arb_ctx)| [filler, *user, filler, *arb_ctx])
+            .collect::<Vec<_>>();
+        let instructions = transaction
+            .iter()
+            .zip(keys.iter())
+            .map(|((data, _), keys)| BorrowedInstruction {
+                program_id: &crate::ID,
+                accounts: keys
+                    .iter()
+                    .map(|pubkey| BorrowedAccountMeta {
+                        pubkey,
+                        is_signer: false,
+                        is_writable: false,
+                    })
+                    .collect(),
+                data,
+            })
+            .collect::<Vec<_>>();
+        let mut data = construct_instructions_data(&instructions);
+
+        let mut results = Vec::new();
+        for (index, (discriminator, arb_ctx)) in transaction.iter().enumerate() {
+            store_current_index(&mut data, index as u16);
+
+            let mut lamports = 0;
+            let owner = Pubkey::default();
+            let sysvar = AccountInfo::new(
+                &INSTRUCTIONS_SYSVAR_ID,
+                false,
+                false,
+                &mut lamports,
+                &mut data,
+                &owner,
+                false,
+                0,
+            );
+            let (expected, search_after) = if *discriminator == INIT {
+                (VERIFY, true)
+            } else {
+                (INIT, false)
+            };
+            results.push(
+                has_paired_context_instruction(&sysvar, expected, user, arb_ctx, search_after)
+                    .unwrap(),
+            );
+        }
+
+        results
+    }
+
+    #[test]
+    fn test_adjacent_pair() {
+        let user = Pubkey::new_unique();
+        let arb_ctx = Pubkey::new_unique();
+        let other_ctx = Pubkey::new_unique();
+
+        assert_eq!(
+            check_pairs(&[(INIT, arb_ctx), (VERIFY, arb_ctx)], &user),
+            [true, true]
+        );
+        // пара другого arb_ctx между ними не мешает
+        assert_eq!(
+            check_pairs(
+                &[
+                    (INIT, arb_ctx),
+                    (INIT, other_ctx),
+                    (VERIFY, other_ctx),
+                    (VERIFY, arb_ctx)
+                ],
+                &user
+            ),
+            [true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_reinit_rejected() {
+        let user = Pubkey::new_unique();
+        let arb_ctx = Pubkey::new_unique();
+
+        // повторный init перед verify: первый init без пары
+        assert_eq!(
+            check_pairs(
+                &[(INIT, arb_ctx), (INIT, arb_ctx), (VERIFY, arb_ctx)],
+                &user
+            ),
+            [false, true, true]
+        );
+        // verify без init и второй verify после уже закрытой пары
+        assert_eq!(check_pairs(&[(VERIFY, arb_ctx)], &user), [false]);
+        assert_eq!(
+            check_pairs(
+                &[(INIT, arb_ctx), (VERIFY, arb_ctx), (VERIFY, arb_ctx)],
+                &user
+            ),
+            [true, true, false]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::ErrorCode;
+use crate::state::arb_context_accounts::{
+    ArbCtx, CloseArbitrageContextAccounts, MigrateArbitrageContextAccounts, ARB_CTX_VERSION,
+    LEGACY_ARB_CTX_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+pub fn migrate_arbitrage_context(ctx: Context<MigrateArbitrageContextAccounts>) -> Result<()> {
+    let arb_ctx = &ctx.accounts.arb_ctx;
+
+    let version = ArbCtx::layout_version(&arb_ctx.try_borrow_data()?)
+        .ok_or(ErrorCode::InvalidArbContextAccount)?;
+
+    // Уже в текущей раскладке - мигрировать нечего
+    if version == ARB_CTX_VERSION {
+        return Ok(());
+    }
+    require!(
+        version == LEGACY_ARB_CTX_VERSION,
+        ErrorCode::ContextVersionMismatch
+    );
+
+    let migrated = ArbCtx::from_legacy(&arb_ctx.try_borrow_data()?)
+        .ok_or(ErrorCode::InvalidArbContextAccount)?;
+    require_keys_eq!(
+        migrated.owner,
+        ctx.accounts.user.key(),
+        ErrorCode::InvalidArbContextAccount
+    );
+
+    let new_len = 8 + ArbCtx::INIT_SPACE;
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(arb_ctx.lamports());
+    if rent_due > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: arb_ctx.to_account_info(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    arb_ctx.resize(new_len)?;
+    migrated.try_serialize(&mut &mut arb_ctx.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+pub fn close_arbitrage_context(ctx: Context<CloseArbitrageContextAccounts>) -> Result<()> {
+    let arb_ctx = &ctx.accounts.arb_ctx;
+
+    require!(
+        ArbCtx::layout_version(&arb_ctx.try_borrow_data()?).is_some(),
+        ErrorCode::InvalidArbContextAccount
+    );
+
+    let user = ctx.accounts.user.to_account_info();
+    **user.try_borrow_mut_lamports()? += arb_ctx.lamports();
+    **arb_ctx.try_borrow_mut_lamports()? = 0;
+
+    arb_ctx.assign(&System::id());
+    arb_ctx.resize(0)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_arb_ctx_layout {
+    use crate::state::arb_context_accounts::*;
+    use anchor_lang::prelude::*;
+    use anchor_lang::Discriminator;
+
+    fn legacy_data(bump: u8, owner: &Pubkey, start_sol: u64, start_src: u64) -> Vec<u8> {
+        let mut data = ArbCtx::DISCRIMINATOR.to_vec();
+        data.push(bump);
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&start_sol.to_le_bytes());
+        data.extend_from_slice(&start_src.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_legacy_layout() {
+        let owner = Pubkey::new_unique();
+        // bump совпадает с текущей версией, но старая раскладка узнаётся по длине
+        let data = legacy_data(ARB_CTX_VERSION, &owner, 5_000_000, 42);
+        assert_eq!(data.len(), LEGACY_ARB_CTX_LEN);
+        assert_eq!(ArbCtx::layout_version(&data), Some(LEGACY_ARB_CTX_VERSION));
+
+        let migrated = ArbCtx::from_legacy(&data).unwrap();
+        assert_eq!(migrated.version, ARB_CTX_VERSION);
+        assert_eq!(migrated.bump, ARB_CTX_VERSION);
+        assert_eq!(migrated.owner, owner);
+        assert_eq!(migrated.start_sol, 5_000_000);
+        assert_eq!(migrated.start_src, 42);
+        assert!(migrated.assets.is_empty());
+    }
+
+    #[test]
+    fn test_current_layout() {
+        let ctx = ArbCtx {
+            version: ARB_CTX_VERSION,
+            bump: 254,
+            owner: Pubkey::new_unique(),
+            start_sol: 1,
+            start_src: 2,
+            assets: Vec::new(),
+        };
+        let mut data = vec![0u8; 8 + ArbCtx::INIT_SPACE];
+        ctx.try_serialize(&mut &mut data[..]).unwrap();
+
+        assert_eq!(ArbCtx::layout_version(&data), Some(ARB_CTX_VERSION));
+        assert!(ArbCtx::from_legacy(&data).is_none());
+
+        // чужой discriminator
+        data[0] ^= 1;
+        assert_eq!(ArbCtx::layout_version(&data), None);
+    }
+}
@@ -1,11 +1,31 @@
-use crate::commons::get_ata_balance;
+use crate::commons::{get_ata_balance, has_paired_context_instruction};
 use crate::error::ErrorCode;
-use crate::state::arb_context_accounts::VerifyArbitrageAccounts;
+use crate::state::arb_context_accounts::{AssetRule, VerifyArbitrageAccounts, ARB_CTX_VERSION};
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
-pub fn verify_arbitrage_context(ctx: Context<VerifyArbitrageAccounts>) -> Result<()> {
+pub fn verify_arbitrage_context<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VerifyArbitrageAccounts<'info>>,
+) -> Result<()> {
     let arb_ctx = &ctx.accounts.arb_ctx;
 
+    require!(
+        arb_ctx.version == ARB_CTX_VERSION,
+        ErrorCode::ContextVersionMismatch
+    );
+
+    // Контекст из предыдущей транзакции не принимается: init должен быть раньше в этой же
+    require!(
+        has_paired_context_instruction(
+            &ctx.accounts.instructions_sysvar,
+            crate::instruction::InitArbitrageContext::DISCRIMINATOR,
+            ctx.accounts.user.key,
+            &arb_ctx.key(),
+            false,
+        )?,
+        ErrorCode::ContextNotInitializedInTransaction
+    );
+
     let user = &ctx.accounts.user;
 
     let curr_sol = user.lamports();
@@ -19,5 +39,70 @@ pub fn verify_arbitrage_context(ctx: Context<VerifyArbitrageAccounts>) -> Result
         ErrorCode::ArbitrageVerificationFailed
     );
 
+    // Remaining accounts должны совпадать со снапшотом по количеству и порядку
+    let asset_accounts = ctx.remaining_accounts;
+    require!(
+        asset_accounts.len() == arb_ctx.assets.len(),
+        ErrorCode::ContextAssetMismatch
+    );
+
+    for (token_account, snapshot) in asset_accounts.iter().zip(arb_ctx.assets.iter()) {
+        require_keys_eq!(
+            token_account.key(),
+            snapshot.token_account,
+            ErrorCode::ContextAssetMismatch
+        );
+
+        if snapshot.rule == AssetRule::Ignore {
+            continue;
+        }
+
+        let curr_amount = get_ata_balance(token_account)?;
+        check_asset_rule(snapshot.rule, snapshot.start_amount, curr_amount)?;
+    }
+
     Ok(())
 }
+
+pub fn check_asset_rule(rule: AssetRule, start_amount: u64, curr_amount: u64) -> Result<()> {
+    let delta = curr_amount as i128 - start_amount as i128;
+
+    let passed = match rule {
+        AssetRule::Ignore => true,
+        AssetRule::NoDecrease => delta >= 0,
+        AssetRule::MinDelta(min_delta) => delta >= min_delta as i128,
+    };
+
+    require!(passed, ErrorCode::ContextAssetRuleViolated);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_check_asset_rule {
+    use super::*;
+
+    #[test]
+    fn test_ignore() {
+        assert!(check_asset_rule(AssetRule::Ignore, u64::MAX, 0).is_ok());
+    }
+
+    #[test]
+    fn test_no_decrease() {
+        assert!(check_asset_rule(AssetRule::NoDecrease, 100, 100).is_ok());
+        assert!(check_asset_rule(AssetRule::NoDecrease, 100, 101).is_ok());
+        assert!(check_asset_rule(AssetRule::NoDecrease, 100, 99).is_err());
+    }
+
+    #[test]
+    fn test_min_delta() {
+        assert!(check_asset_rule(AssetRule::MinDelta(10), 100, 110).is_ok());
+        assert!(check_asset_rule(AssetRule::MinDelta(10), 100, 109).is_err());
+
+        // отрицательная дельта допускает ограниченную потерю
+        assert!(check_asset_rule(AssetRule::MinDelta(-5), 100, 95).is_ok());
+        assert!(check_asset_rule(AssetRule::MinDelta(-5), 100, 94).is_err());
+
+        assert!(check_asset_rule(AssetRule::MinDelta(i64::MAX), 0, u64::MAX).is_ok());
+    }
+}
@@ -1,14 +1,14 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::associated_token::{create, Create};
-use anchor_spl::token::Token;
 
 pub fn create_ata_if_missing<'info>(
     payer: &Signer<'info>,
+    authority: &AccountInfo<'info>,
     ata_account: &AccountInfo<'info>,
     mint: &AccountInfo<'info>,
     system_program: &Program<'info, System>,
-    token_program: &Program<'info, anchor_spl::token::Token>,
+    token_program: &AccountInfo<'info>,
     associated_token_program: &Program<'info, AssociatedToken>,
 ) -> Result<bool> {
     if !ata_account.data_is_empty() {
@@ -18,10 +18,10 @@ pub fn create_ata_if_missing<'info>(
     let cpi_accounts = Create {
         payer: payer.to_account_info(),
         associated_token: ata_account.clone(),
-        authority: payer.to_account_info(),
+        authority: authority.clone(),
         mint: mint.clone(),
         system_program: system_program.to_account_info(),
-        token_program: token_program.to_account_info(),
+        token_program: token_program.clone(),
     };
 
     let cpi_ctx = CpiContext::new(associated_token_program.to_account_info(), cpi_accounts);
@@ -35,7 +35,7 @@ pub fn create_atas_if_missing<'info>(
     payer: &Signer<'info>,
     ata_mint_pairs: Vec<(&AccountInfo<'info>, &AccountInfo<'info>)>,
     system_program: &Program<'info, System>,
-    token_program: &Program<'info, Token>,
+    token_program: &AccountInfo<'info>,
     associated_token_program: &Program<'info, AssociatedToken>,
 ) -> Result<Vec<bool>> {
     let mut created_flags = Vec::new();
@@ -48,7 +48,7 @@ pub fn create_atas_if_missing<'info>(
                 authority: payer.to_account_info(),
                 mint: mint.clone(),
                 system_program: system_program.to_account_info(),
-                token_program: token_program.to_account_info(),
+                token_program: token_program.clone(),
             };
 
             let cpi_ctx = CpiContext::new(associated_token_program.to_account_info(), cpi_accounts);
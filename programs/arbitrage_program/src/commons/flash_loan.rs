@@ -0,0 +1,350 @@
+use crate::dex::is_flash_loan_program;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+// Дискриминаторы Kamino Lending (и programs/mock_lending)
+pub const FLASH_BORROW_DISCRIMINATOR: [u8; 8] = [135, 231, 52, 167, 7, 52, 212, 193];
+pub const FLASH_REPAY_DISCRIMINATOR: [u8; 8] = [185, 117, 0, 203, 96, 245, 180, 186];
+
+// user_destination_liquidity в borrow и user_source_liquidity в repay
+pub const FLASH_LOAN_USER_LIQUIDITY_INDEX: usize = 6;
+
+/// Займ, найденный в транзакции вокруг текущей инструкции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashLoan {
+    pub borrow_index: usize,
+    pub amount: u64,
+}
+
+/// Ищет borrow на token_account до текущей инструкции и парный ему repay после неё
+pub fn find_flash_loan(
+    instructions_sysvar: &AccountInfo,
+    token_account: &Pubkey,
+) -> Result<FlashLoan> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+
+    let mut borrow = None;
+    for index in (0..current_index).rev() {
+        let ix = load_instruction_at_checked(index, instructions_sysvar)?;
+        if let Some(amount) = parse_flash_borrow(&ix, token_account) {
+            borrow = Some(FlashLoan {
+                borrow_index: index,
+                amount,
+            });
+            break;
+        }
+    }
+    let borrow = borrow.ok_or(ErrorCode::FlashLoanBorrowNotFound)?;
+
+    let mut index = current_index + 1;
+    // за пределами транзакции load_instruction_at_checked возвращает ошибку
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+
+        if parse_flash_repay(&ix, token_account, borrow.borrow_index)
+            .is_some_and(|amount| amount >= borrow.amount)
+        {
+            return Ok(borrow);
+        }
+    }
+
+    Err(ErrorCode::FlashLoanRepayNotFound.into())
+}
+
+/// Сумма займа, если ix - flash borrow на token_account
+pub fn parse_flash_borrow(ix: &Instruction, token_account: &Pubkey) -> Option<u64> {
+    if !is_flash_loan_program(&ix.program_id) || !ix.data.starts_with(&FLASH_BORROW_DISCRIMINATOR) {
+        return None;
+    }
+
+    user_liquidity_matches(ix, token_account)?;
+    read_u64(&ix.data, 8)
+}
+
+/// Сумма возврата, если ix - flash repay с token_account для borrow с индексом borrow_index
+pub fn parse_flash_repay(
+    ix: &Instruction,
+    token_account: &Pubkey,
+    borrow_index: usize,
+) -> Option<u64> {
+    if !is_flash_loan_program(&ix.program_id) || !ix.data.starts_with(&FLASH_REPAY_DISCRIMINATOR) {
+        return None;
+    }
+
+    user_liquidity_matches(ix, token_account)?;
+    if *ix.data.get(16)? as usize != borrow_index {
+        return None;
+    }
+
+    read_u64(&ix.data, 8)
+}
+
+fn user_liquidity_matches(ix: &Instruction, token_account: &Pubkey) -> Option<()> {
+    ix.accounts
+        .get(FLASH_LOAN_USER_LIQUIDITY_INDEX)
+        .filter(|meta| meta.pubkey == *token_account)
+        .map(|_| ())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests_flash_loan {
+    use super::*;
+    use crate::dex::KAMINO_LENDING_PROGRAM_ID;
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    fn lending_ix(discriminator: [u8; 8], token_account: Pubkey, args: &[u8]) -> Instruction {
+        let mut accounts = (0..FLASH_LOAN_USER_LIQUIDITY_INDEX)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect::<Vec<_>>();
+        accounts.push(AccountMeta::new(token_account, false));
+
+        Instruction {
+            program_id: KAMINO_LENDING_PROGRAM_ID,
+            accounts,
+            data: [discriminator.as_slice(), args].concat(),
+        }
+    }
+
+    #[test]
+    fn test_parse_borrow() {
+        let token_account = Pubkey::new_unique();
+        let ix = lending_ix(
+            FLASH_BORROW_DISCRIMINATOR,
+            token_account,
+            &100u64.to_le_bytes(),
+        );
+
+        assert_eq!(parse_flash_borrow(&ix, &token_account), Some(100));
+        assert_eq!(parse_flash_borrow(&ix, &Pubkey::new_unique()), None);
+        assert_eq!(parse_flash_repay(&ix, &token_account, 0), None);
+
+        let mut other_program = ix.clone();
+        other_program.program_id = Pubkey::new_unique();
+        assert_eq!(parse_flash_borrow(&other_program, &token_account), None);
+    }
+
+    #[test]
+    fn test_parse_repay() {
+        let token_account = Pubkey::new_unique();
+        let args = [100u64.to_le_bytes().as_slice(), &[2]].concat();
+        let ix = lending_ix(FLASH_REPAY_DISCRIMINATOR, token_account, &args);
+
+        assert_eq!(parse_flash_repay(&ix, &token_account, 2), Some(100));
+        // repay другого borrow
+        assert_eq!(parse_flash_repay(&ix, &token_account, 1), None);
+        assert_eq!(parse_flash_borrow(&ix, &token_account), None);
+
+        // обрезанные данные
+        let short = lending_ix(FLASH_REPAY_DISCRIMINATOR, token_account, &[1, 2, 3]);
+        assert_eq!(parse_flash_repay(&short, &token_account, 0), None);
+    }
+}
+
+#[cfg(test)]
+mod tests_flash_loan_transaction {
+    use super::*;
+    use crate::dex::KAMINO_LENDING_PROGRAM_ID;
+    use anchor_lang::solana_program::sysvar::instructions::{
+        construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction,
+        ID as INSTRUCTIONS_SYSVAR_ID,
+    };
+    use anchor_lang::{Discriminator, InstructionData};
+
+    // Без dex-program-overrides мок принимается под адресом Kamino: так его и грузят в локальный валидатор
+    const LENDER_ID: Pubkey = if cfg!(feature = "dex-program-overrides") {
+        mock_lending::ID
+    } else {
+        KAMINO_LENDING_PROGRAM_ID
+    };
+
+    fn borrow_ix(token_account: Pubkey, liquidity_amount: u64) -> Instruction {
+        let accounts = mock_lending::accounts::FlashBorrowReserveLiquidity {
+            user_transfer_authority: Pubkey::new_unique(),
+            lending_market_authority: Pubkey::new_unique(),
+            lending_market: Pubkey::new_unique(),
+            reserve: Pubkey::new_unique(),
+            reserve_liquidity_mint: Pubkey::new_unique(),
+            reserve_source_liquidity: Pubkey::new_unique(),
+            user_destination_liquidity: token_account,
+            sysvar_info: INSTRUCTIONS_SYSVAR_ID,
+            token_program: anchor_spl::token::ID,
+        };
+
+        Instruction {
+            program_id: LENDER_ID,
+            accounts: accounts.to_account_metas(None),
+            data: mock_lending::instruction::FlashBorrowReserveLiquidity { liquidity_amount }
+                .data(),
+        }
+    }
+
+    fn repay_ix(token_account: Pubkey, liquidity_amount: u64, borrow_index: u8) -> Instruction {
+        let accounts = mock_lending::accounts::FlashRepayReserveLiquidity {
+            user_transfer_authority: Pubkey::new_unique(),
+            lending_market_authority: Pubkey::new_unique(),
+            lending_market: Pubkey::new_unique(),
+            reserve: Pubkey::new_unique(),
+            reserve_liquidity_mint: Pubkey::new_unique(),
+            reserve_destination_liquidity: Pubkey::new_unique(),
+            user_source_liquidity: token_account,
+            sysvar_info: INSTRUCTIONS_SYSVAR_ID,
+            token_program: anchor_spl::token::ID,
+        };
+
+        Instruction {
+            program_id: LENDER_ID,
+            accounts: accounts.to_account_metas(None),
+            data: mock_lending::instruction::FlashRepayReserveLiquidity {
+                liquidity_amount,
+                borrow_instruction_index: borrow_index,
+            }
+            .data(),
+        }
+    }
+
+    fn arb_ix() -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: vec![],
+            data: crate::instruction::TwoPoolArb::DISCRIMINATOR.to_vec(),
+        }
+    }
+
+    /// find_flash_loan из инструкции arb_index транзакции
+    fn find(
+        transaction: &[Instruction],
+        arb_index: u16,
+        token_account: &Pubkey,
+    ) -> Result<FlashLoan> {
+        let metas = transaction
+            .iter()
+            .map(|ix| {
+                ix.accounts
+                    .iter()
+                    .map(|meta| BorrowedAccountMeta {
+                        pubkey: &meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let instructions = transaction
+            .iter()
+            .zip(metas)
+            .map(|(ix, accounts)| BorrowedInstruction {
+                program_id: &ix.program_id,
+                accounts,
+                data: &ix.data,
+            })
+            .collect::<Vec<_>>();
+        let mut data = construct_instructions_data(&instructions);
+        store_current_index(&mut data, arb_index);
+
+        let mut lamports = 0;
+        let owner = Pubkey::default();
+        let sysvar = AccountInfo::new(
+            &INSTRUCTIONS_SYSVAR_ID,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+
+        find_flash_loan(&sysvar, token_account)
+    }
+
+    #[test]
+    fn test_borrow_arb_repay() {
+        let token_account = Pubkey::new_unique();
+
+        let transaction = [
+            borrow_ix(token_account, 1_000),
+            arb_ix(),
+            repay_ix(token_account, 1_000, 0),
+        ];
+        assert_eq!(
+            find(&transaction, 1, &token_account).unwrap(),
+            FlashLoan {
+                borrow_index: 0,
+                amount: 1_000
+            }
+        );
+
+        // borrow_instruction_index считается от начала транзакции, а не от arb
+        let transaction = [
+            arb_ix(),
+            borrow_ix(token_account, 1_000),
+            arb_ix(),
+            repay_ix(token_account, 1_000, 1),
+        ];
+        assert_eq!(
+            find(&transaction, 2, &token_account).unwrap().borrow_index,
+            1
+        );
+    }
+
+    #[test]
+    fn test_unmatched_loans() {
+        let token_account = Pubkey::new_unique();
+        let borrow_not_found: Error = ErrorCode::FlashLoanBorrowNotFound.into();
+        let repay_not_found: Error = ErrorCode::FlashLoanRepayNotFound.into();
+
+        // займ на другой аккаунт
+        let other = Pubkey::new_unique();
+        let transaction = [borrow_ix(other, 1_000), arb_ix(), repay_ix(other, 1_000, 0)];
+        assert_eq!(
+            find(&transaction, 1, &token_account).unwrap_err(),
+            borrow_not_found
+        );
+
+        // repay отсутствует, меньше займа или относится к другому borrow
+        let transaction = [borrow_ix(token_account, 1_000), arb_ix()];
+        assert_eq!(
+            find(&transaction, 1, &token_account).unwrap_err(),
+            repay_not_found
+        );
+        let transaction = [
+            borrow_ix(token_account, 1_000),
+            arb_ix(),
+            repay_ix(token_account, 999, 0),
+        ];
+        assert_eq!(
+            find(&transaction, 1, &token_account).unwrap_err(),
+            repay_not_found
+        );
+        let transaction = [
+            borrow_ix(token_account, 1_000),
+            arb_ix(),
+            repay_ix(token_account, 1_000, 5),
+        ];
+        assert_eq!(
+            find(&transaction, 1, &token_account).unwrap_err(),
+            repay_not_found
+        );
+
+        // repay до arb не считается
+        let transaction = [
+            borrow_ix(token_account, 1_000),
+            repay_ix(token_account, 1_000, 0),
+            arb_ix(),
+        ];
+        assert_eq!(
+            find(&transaction, 2, &token_account).unwrap_err(),
+            repay_not_found
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token_interface::TokenAccount;
 
+// Читает и legacy, и Token-2022 аккаунты (с расширениями)
 pub fn get_ata_balance(ata_account: &AccountInfo) -> Result<u64> {
     let token_account = TokenAccount::try_deserialize(&mut ata_account.data.borrow().as_ref())?;
     Ok(token_account.amount)
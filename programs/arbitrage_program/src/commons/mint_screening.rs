@@ -0,0 +1,211 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022;
+
+// Раскладка Mint: freeze_authority - COption<Pubkey> после mint_authority, supply, decimals, is_initialized
+const FREEZE_AUTHORITY_OFFSET: usize = 46;
+// Token-2022: байт AccountType после паддинга до размера Account, затем TLV расширения
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const TLV_START: usize = ACCOUNT_TYPE_OFFSET + 1;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+// Номера ExtensionType Token-2022
+const EXT_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXT_NON_TRANSFERABLE: u16 = 9;
+const EXT_PERMANENT_DELEGATE: u16 = 12;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_PAUSABLE: u16 = 26;
+
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// Какие свойства intermediate минта запрещают сделку, каждая проверка включается отдельно
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, Default)]
+pub struct MintScreening {
+    pub reject_freeze_authority: bool,
+    pub reject_transfer_hook: bool,
+    pub reject_permanent_delegate: bool,
+    pub reject_non_transferable: bool,
+    pub reject_pausable: bool,
+    pub reject_default_frozen: bool,
+}
+
+/// Отклоняет минт, который может заблокировать средства
+pub fn screen_mint(mint: &AccountInfo, screening: &MintScreening) -> Result<()> {
+    let data = mint.try_borrow_data()?;
+
+    screen_mint_data(&data, mint.owner == &spl_token_2022::ID, screening)
+}
+
+pub fn screen_mint_data(data: &[u8], is_token_2022: bool, screening: &MintScreening) -> Result<()> {
+    require!(
+        data.len() >= FREEZE_AUTHORITY_OFFSET + 36,
+        ErrorCode::InvalidAccount
+    );
+
+    if screening.reject_freeze_authority {
+        require!(
+            !is_some_pubkey(&data[FREEZE_AUTHORITY_OFFSET..FREEZE_AUTHORITY_OFFSET + 36]),
+            ErrorCode::MintHasFreezeAuthority
+        );
+    }
+
+    // У legacy минтов и Token-2022 без расширений TLV нет
+    if !is_token_2022 || data.len() <= TLV_START {
+        return Ok(());
+    }
+    require!(
+        data[ACCOUNT_TYPE_OFFSET] == ACCOUNT_TYPE_MINT,
+        ErrorCode::InvalidAccount
+    );
+
+    let mut offset = TLV_START;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value_start = offset + 4;
+        let value_end = value_start + length;
+
+        // Uninitialized - дальше только нули
+        if extension_type == 0 {
+            break;
+        }
+        require!(value_end <= data.len(), ErrorCode::InvalidAccount);
+        let value = &data[value_start..value_end];
+
+        match extension_type {
+            // authority, program_id: хук активен, если program_id задан
+            EXT_TRANSFER_HOOK if screening.reject_transfer_hook => {
+                require!(
+                    value.len() < 64 || value[32..64].iter().all(|b| *b == 0),
+                    ErrorCode::MintHasTransferHook
+                );
+            }
+            EXT_PERMANENT_DELEGATE if screening.reject_permanent_delegate => {
+                require!(
+                    value.iter().all(|b| *b == 0),
+                    ErrorCode::MintHasPermanentDelegate
+                );
+            }
+            EXT_NON_TRANSFERABLE if screening.reject_non_transferable => {
+                return err!(ErrorCode::MintNonTransferable);
+            }
+            EXT_PAUSABLE if screening.reject_pausable => {
+                return err!(ErrorCode::MintPausable);
+            }
+            EXT_DEFAULT_ACCOUNT_STATE if screening.reject_default_frozen => {
+                require!(
+                    value.first() != Some(&ACCOUNT_STATE_FROZEN),
+                    ErrorCode::MintDefaultStateFrozen
+                );
+            }
+            _ => {}
+        }
+
+        offset = value_end;
+    }
+
+    Ok(())
+}
+
+// COption<Pubkey>: u32 тег + 32 байта ключа
+fn is_some_pubkey(data: &[u8]) -> bool {
+    u32::from_le_bytes([data[0], data[1], data[2], data[3]]) == 1
+}
+
+#[cfg(test)]
+mod tests_mint_screening {
+    use super::*;
+
+    const ALL: MintScreening = MintScreening {
+        reject_freeze_authority: true,
+        reject_transfer_hook: true,
+        reject_permanent_delegate: true,
+        reject_non_transferable: true,
+        reject_pausable: true,
+        reject_default_frozen: true,
+    };
+
+    fn mint(freeze_authority: bool) -> Vec<u8> {
+        let mut data = vec![0u8; 82];
+        if freeze_authority {
+            data[FREEZE_AUTHORITY_OFFSET] = 1;
+            data[FREEZE_AUTHORITY_OFFSET + 4..FREEZE_AUTHORITY_OFFSET + 36].fill(7);
+        }
+        data
+    }
+
+    fn mint_2022(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = mint(false);
+        data.resize(ACCOUNT_TYPE_OFFSET, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn rejected(data: &[u8], screening: &MintScreening) -> anchor_lang::error::Error {
+        screen_mint_data(data, true, screening).unwrap_err()
+    }
+
+    #[test]
+    fn test_freeze_authority() {
+        assert!(screen_mint_data(&mint(false), false, &ALL).is_ok());
+        assert_eq!(
+            screen_mint_data(&mint(true), false, &ALL).unwrap_err(),
+            ErrorCode::MintHasFreezeAuthority.into()
+        );
+        assert!(screen_mint_data(&mint(true), false, &MintScreening::default()).is_ok());
+    }
+
+    #[test]
+    fn test_extensions() {
+        let hook = mint_2022(&[(EXT_TRANSFER_HOOK, [vec![0; 32], vec![1; 32]].concat())]);
+        assert_eq!(rejected(&hook, &ALL), ErrorCode::MintHasTransferHook.into());
+
+        // хук без program_id не мешает
+        let empty_hook = mint_2022(&[(EXT_TRANSFER_HOOK, vec![0; 64])]);
+        assert!(screen_mint_data(&empty_hook, true, &ALL).is_ok());
+
+        let delegate = mint_2022(&[(EXT_PERMANENT_DELEGATE, vec![3; 32])]);
+        assert_eq!(
+            rejected(&delegate, &ALL),
+            ErrorCode::MintHasPermanentDelegate.into()
+        );
+
+        let non_transferable = mint_2022(&[(EXT_NON_TRANSFERABLE, vec![])]);
+        assert_eq!(
+            rejected(&non_transferable, &ALL),
+            ErrorCode::MintNonTransferable.into()
+        );
+
+        let pausable = mint_2022(&[(EXT_PAUSABLE, vec![0; 33])]);
+        assert_eq!(rejected(&pausable, &ALL), ErrorCode::MintPausable.into());
+
+        let frozen = mint_2022(&[(EXT_DEFAULT_ACCOUNT_STATE, vec![ACCOUNT_STATE_FROZEN])]);
+        assert_eq!(
+            rejected(&frozen, &ALL),
+            ErrorCode::MintDefaultStateFrozen.into()
+        );
+        let initialized = mint_2022(&[(EXT_DEFAULT_ACCOUNT_STATE, vec![1])]);
+        assert!(screen_mint_data(&initialized, true, &ALL).is_ok());
+    }
+
+    #[test]
+    fn test_toggles() {
+        let data = mint_2022(&[(EXT_PAUSABLE, vec![0; 33]), (EXT_NON_TRANSFERABLE, vec![])]);
+
+        assert!(screen_mint_data(&data, true, &MintScreening::default()).is_ok());
+
+        let only_non_transferable = MintScreening {
+            reject_non_transferable: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            rejected(&data, &only_non_transferable),
+            ErrorCode::MintNonTransferable.into()
+        );
+    }
+}
@@ -9,3 +9,27 @@ pub use get_ata_balance::*;
 
 pub mod arbitrage_context;
 pub use arbitrage_context::*;
+
+pub mod swap_authority;
+pub use swap_authority::*;
+
+pub mod profit_split;
+pub use profit_split::*;
+
+pub mod tip;
+pub use tip::*;
+
+pub mod token_program;
+pub use token_program::*;
+
+pub mod mint_screening;
+pub use mint_screening::*;
+
+pub mod wsol;
+pub use wsol::*;
+
+pub mod flash_loan;
+pub use flash_loan::*;
+
+pub mod arb_execution;
+pub use arb_execution::*;
@@ -0,0 +1,121 @@
+use crate::commons::{
+    invoke_transfer, resolve_token_program, transfer_lamports_to_wsol, SwapAuthority,
+};
+use crate::error::ErrorCode;
+use crate::state::{CommonAccounts, ProfitSplit};
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::Mint;
+
+/// Переводит токены с аккаунта, которым владеет authority (пользователь или vault).
+/// transfer_checked: Token-2022 отклоняет unchecked transfer для минтов с TransferFeeConfig
+pub fn transfer_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &SwapAuthority<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    let decimals = Mint::try_deserialize(&mut mint.data.borrow().as_ref())?.decimals;
+
+    let ix = anchor_spl::token_2022::spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source.key,
+        mint.key,
+        destination.key,
+        &authority.key(),
+        &[],
+        amount,
+        decimals,
+    )?;
+
+    authority.invoke(
+        &ix,
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.to_account_info(),
+            token_program.clone(),
+        ],
+    )
+}
+
+/// Получатели долей прибыли, ключи проверяются constraint-ами по конфигу
+pub struct ProfitDestinations<'b, 'info> {
+    pub treasury_token_account: Option<&'b AccountInfo<'info>>,
+    pub operator_token_account: Option<&'b AccountInfo<'info>>,
+    pub tip_account: Option<&'b AccountInfo<'info>>,
+}
+
+/// Распределяет реализованную прибыль в source токене по долям из конфига
+pub fn distribute_profit<'info>(
+    split: &ProfitSplit,
+    profit: u64,
+    common: &CommonAccounts<'info>,
+    authority: &SwapAuthority<'_, 'info>,
+    destinations: &ProfitDestinations<'_, 'info>,
+    profit_in_lamports: bool,
+) -> Result<()> {
+    if profit == 0 || !split.is_enabled() {
+        return Ok(());
+    }
+
+    let token_program = resolve_token_program(&common.user_source_token_mint, common)?;
+    let source_token_account = &common.user_source_token_account;
+
+    // Чаевые из конфига вычитаются из прибыли до деления на доли
+    let is_native_source = common.user_source_token_mint.key() == spl_token::native_mint::ID;
+    let (tip, profit) = split.split_tip(profit, is_native_source)?;
+
+    let (treasury_share, operator_share) = split.shares(profit);
+
+    if treasury_share > 0 {
+        let treasury = destinations
+            .treasury_token_account
+            .ok_or(ErrorCode::InvalidProfitSplitAccount)?;
+        if profit_in_lamports {
+            transfer_lamports_to_wsol(common, treasury, treasury_share)?;
+        } else {
+            transfer_tokens(
+                &token_program,
+                source_token_account,
+                &common.user_source_token_mint,
+                treasury,
+                authority,
+                treasury_share,
+            )?;
+        }
+    }
+
+    if operator_share > 0 {
+        let operator = destinations
+            .operator_token_account
+            .ok_or(ErrorCode::InvalidProfitSplitAccount)?;
+        // Без vault прибыль и так остаётся у оператора на source аккаунте
+        if operator.key != source_token_account.key {
+            if profit_in_lamports {
+                transfer_lamports_to_wsol(common, operator, operator_share)?;
+            } else {
+                transfer_tokens(
+                    &token_program,
+                    source_token_account,
+                    &common.user_source_token_mint,
+                    operator,
+                    authority,
+                    operator_share,
+                )?;
+            }
+        }
+    }
+
+    if tip > 0 {
+        let tip_account = destinations
+            .tip_account
+            .ok_or(ErrorCode::InvalidProfitSplitAccount)?;
+        invoke_transfer(&common.user, tip_account, &common.system_program, tip)?;
+    }
+
+    Ok(())
+}
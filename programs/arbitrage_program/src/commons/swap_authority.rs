@@ -0,0 +1,198 @@
+use crate::commons::resolve_token_program;
+use crate::error::ErrorCode;
+use crate::state::{CommonAccounts, VaultSignerAccounts, VAULT_AUTHORITY_SEED};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::TokenAccount;
+
+/// Кто подписывает CPI в DEX: кошелёк пользователя или PDA vault-а
+#[derive(Clone)]
+pub enum SwapAuthority<'b, 'info> {
+    User(&'b Signer<'info>),
+    Vault {
+        authority: &'b AccountInfo<'info>,
+        vault: Pubkey,
+        bump: u8,
+    },
+}
+
+impl<'b, 'info> SwapAuthority<'b, 'info> {
+    /// Без vault-аккаунтов подписывает пользователь, с ними - vault_authority.
+    /// В режиме vault подписант должен быть оператором vault-а, а source и intermediate
+    /// аккаунты - принадлежать vault_authority, иначе PDA подпишет вывод на чужой аккаунт
+    pub fn resolve(
+        common: &'b CommonAccounts<'info>,
+        vault_signer: &'b VaultSignerAccounts<'info>,
+    ) -> Result<Self> {
+        match (
+            vault_signer.vault.as_ref(),
+            vault_signer.vault_authority.as_ref(),
+        ) {
+            (None, None) => Ok(SwapAuthority::User(&common.user)),
+            (Some(vault), Some(vault_authority)) => {
+                require_keys_eq!(
+                    vault.authority,
+                    vault_authority.key(),
+                    ErrorCode::InvalidVaultAuthority
+                );
+                require!(
+                    vault.operators.contains(common.user.key),
+                    ErrorCode::VaultOperatorNotAllowed
+                );
+
+                check_vault_token_account(
+                    &common.user_source_token_account,
+                    &common.user_source_token_mint,
+                    &vault.authority,
+                    common,
+                    false,
+                )?;
+                // пустой intermediate создаётся create_ata_if_missing как ATA vault_authority
+                check_vault_token_account(
+                    &common.user_intermediate_token_account,
+                    &common.user_intermediate_token_mint,
+                    &vault.authority,
+                    common,
+                    true,
+                )?;
+
+                Ok(SwapAuthority::Vault {
+                    authority: vault_authority.as_ref(),
+                    vault: vault.key(),
+                    bump: vault.authority_bump,
+                })
+            }
+            _ => Err(ErrorCode::InvalidVaultAuthority.into()),
+        }
+    }
+
+    pub fn key(&self) -> Pubkey {
+        match self {
+            SwapAuthority::User(user) => user.key(),
+            SwapAuthority::Vault { authority, .. } => authority.key(),
+        }
+    }
+
+    pub fn to_account_info(&self) -> AccountInfo<'info> {
+        match self {
+            SwapAuthority::User(user) => user.to_account_info(),
+            SwapAuthority::Vault { authority, .. } => (*authority).clone(),
+        }
+    }
+
+    /// invoke для пользователя, invoke_signed с seeds vault_authority для vault
+    pub fn invoke(&self, ix: &Instruction, account_infos: &[AccountInfo<'info>]) -> Result<()> {
+        match self {
+            SwapAuthority::User(_) => invoke_signed(ix, account_infos, &[])?,
+            SwapAuthority::Vault { vault, bump, .. } => invoke_signed(
+                ix,
+                account_infos,
+                &[&[VAULT_AUTHORITY_SEED, vault.as_ref(), &[*bump]]],
+            )?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Токен-аккаунт vault-а: программа минта, нужный минт и владелец vault_authority
+fn check_vault_token_account<'info>(
+    token_account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    vault_authority: &Pubkey,
+    common: &CommonAccounts<'info>,
+    allow_empty: bool,
+) -> Result<()> {
+    if allow_empty && token_account.data_is_empty() {
+        return Ok(());
+    }
+
+    let token_program = resolve_token_program(mint, common)?;
+
+    check_token_account_owner(token_account, token_program.key, mint.key, vault_authority)
+}
+
+fn check_token_account_owner(
+    token_account: &AccountInfo,
+    token_program: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(
+        *token_account.owner,
+        *token_program,
+        ErrorCode::InvalidVaultTokenAccount
+    );
+
+    let account = TokenAccount::try_deserialize(&mut token_account.data.borrow().as_ref())
+        .map_err(|_| error!(ErrorCode::InvalidVaultTokenAccount))?;
+    require!(
+        account.owner == *authority && account.mint == *mint,
+        ErrorCode::InvalidVaultTokenAccount
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_vault_token_account {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token;
+
+    const MINT: Pubkey = Pubkey::new_from_array([1; 32]);
+    const AUTHORITY: Pubkey = Pubkey::new_from_array([2; 32]);
+
+    fn check(owner_program: Pubkey, mint: Pubkey, authority: Pubkey) -> Result<()> {
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner: authority,
+            amount: 1_000,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner_program,
+            false,
+            0,
+        );
+
+        check_token_account_owner(&account, &spl_token::ID, &MINT, &AUTHORITY)
+    }
+
+    #[test]
+    fn test_vault_owned() {
+        assert!(check(spl_token::ID, MINT, AUTHORITY).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_foreign_accounts() {
+        let expected: Error = ErrorCode::InvalidVaultTokenAccount.into();
+
+        // аккаунт оператора с тем же минтом
+        assert_eq!(
+            check(spl_token::ID, MINT, Pubkey::new_unique()).unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            check(spl_token::ID, Pubkey::new_unique(), AUTHORITY).unwrap_err(),
+            expected
+        );
+        // подделка с теми же данными, но не у токен-программы
+        assert_eq!(
+            check(Pubkey::new_unique(), MINT, AUTHORITY).unwrap_err(),
+            expected
+        );
+    }
+}
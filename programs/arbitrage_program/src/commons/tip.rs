@@ -0,0 +1,159 @@
+use crate::arbitrage_engine::TipParams;
+use crate::commons::{invoke_transfer, transfer_tokens, SwapAuthority};
+use crate::error::ErrorCode;
+use crate::state::CommonAccounts;
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::TokenAccount;
+
+/// Платит max(min_tip, profit * tip_bps / 10000) лампортов на tip_account и возвращает остаток прибыли.
+/// Source токен - WSOL (см. TipParams::check_source), чаевые берутся из прибыли: WSOL переводится
+/// на пустой unwrap-аккаунт, который закрывается на пользователя, и лампорты уходят на tip_account.
+pub fn pay_tip<'info>(
+    tip_params: &TipParams,
+    profit: u64,
+    common: &CommonAccounts<'info>,
+    authority: &SwapAuthority<'_, 'info>,
+    tip_account: &AccountInfo<'info>,
+    unwrap_token_account: Option<&AccountInfo<'info>>,
+    profit_in_lamports: bool,
+) -> Result<u64> {
+    let tip = tip_params.calc_tip(profit);
+    if tip == 0 {
+        return Ok(profit);
+    }
+
+    TipParams::check_source(common.user_source_token_mint.key)?;
+    require!(tip <= profit, ErrorCode::TipExceedsProfit);
+
+    // В режиме wrap_sol прибыль уже развёрнута в лампорты пользователя
+    if !profit_in_lamports {
+        let unwrap_token_account = unwrap_token_account.ok_or(ErrorCode::MissingUnwrapAccount)?;
+        let token_program = common.token_program.to_account_info();
+
+        // Закрытие отдаёт пользователю все лампорты аккаунта: только пустой WSOL аккаунт authority
+        check_unwrap_account(
+            unwrap_token_account,
+            &common.user_source_token_account.key(),
+            &authority.key(),
+        )?;
+
+        transfer_tokens(
+            &token_program,
+            &common.user_source_token_account,
+            &common.user_source_token_mint,
+            unwrap_token_account,
+            authority,
+            tip,
+        )?;
+
+        let ix = spl_token::instruction::close_account(
+            token_program.key,
+            unwrap_token_account.key,
+            common.user.key,
+            &authority.key(),
+            &[],
+        )?;
+
+        authority.invoke(
+            &ix,
+            &[
+                unwrap_token_account.clone(),
+                common.user.to_account_info(),
+                authority.to_account_info(),
+                token_program,
+            ],
+        )?;
+    }
+
+    invoke_transfer(&common.user, tip_account, &common.system_program, tip)?;
+
+    Ok(profit - tip)
+}
+
+/// Unwrap-аккаунт чаевых: WSOL аккаунт authority с нулевым балансом, не source аккаунт
+fn check_unwrap_account(
+    unwrap_token_account: &AccountInfo,
+    source_token_account: &Pubkey,
+    authority: &Pubkey,
+) -> Result<()> {
+    require_keys_neq!(
+        unwrap_token_account.key(),
+        *source_token_account,
+        ErrorCode::InvalidTipUnwrapAccount
+    );
+    require_keys_eq!(
+        *unwrap_token_account.owner,
+        spl_token::ID,
+        ErrorCode::InvalidTipUnwrapAccount
+    );
+
+    let account = TokenAccount::try_deserialize(&mut unwrap_token_account.data.borrow().as_ref())
+        .map_err(|_| error!(ErrorCode::InvalidTipUnwrapAccount))?;
+    require!(
+        account.mint == spl_token::native_mint::ID
+            && account.owner == *authority
+            && account.amount == 0,
+        ErrorCode::InvalidTipUnwrapAccount
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_unwrap_account {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+
+    const AUTHORITY: Pubkey = Pubkey::new_from_array([1; 32]);
+    const SOURCE: Pubkey = Pubkey::new_from_array([2; 32]);
+
+    fn check(key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) -> Result<()> {
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &spl_token::ID,
+            false,
+            0,
+        );
+
+        check_unwrap_account(&account, &SOURCE, &AUTHORITY)
+    }
+
+    #[test]
+    fn test_empty_wsol_account() {
+        let native = spl_token::native_mint::ID;
+        let expected: Error = ErrorCode::InvalidTipUnwrapAccount.into();
+
+        assert!(check(Pubkey::new_unique(), native, AUTHORITY, 0).is_ok());
+
+        // source аккаунт, аккаунт с балансом, чужой и не WSOL
+        assert_eq!(check(SOURCE, native, AUTHORITY, 0).unwrap_err(), expected);
+        assert_eq!(
+            check(Pubkey::new_unique(), native, AUTHORITY, 1).unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            check(Pubkey::new_unique(), native, Pubkey::new_unique(), 0).unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            check(Pubkey::new_unique(), Pubkey::new_unique(), AUTHORITY, 0).unwrap_err(),
+            expected
+        );
+    }
+}
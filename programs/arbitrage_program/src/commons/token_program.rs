@@ -0,0 +1,110 @@
+use crate::error::ErrorCode;
+use crate::state::CommonAccounts;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    TransferFee, TransferFeeConfig,
+};
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+
+/// Токен-программа минта по его владельцу: legacy Token или Token-2022
+pub fn resolve_token_program<'info>(
+    mint: &AccountInfo<'info>,
+    common: &CommonAccounts<'info>,
+) -> Result<AccountInfo<'info>> {
+    if mint.owner == common.token_program.key {
+        Ok(common.token_program.to_account_info())
+    } else if mint.owner == common.token_program_2022.key {
+        Ok(common.token_program_2022.to_account_info())
+    } else {
+        Err(ErrorCode::InvalidTokenProgram.into())
+    }
+}
+
+/// Комиссия transfer-fee расширения Token-2022 для текущей эпохи, None - комиссии нет
+pub fn get_transfer_fee(mint: &AccountInfo) -> Result<Option<TransferFee>> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(None);
+    };
+
+    let epoch = Clock::get()?.epoch;
+
+    Ok(Some(*transfer_fee_config.get_epoch_fee(epoch)))
+}
+
+/// Сколько дойдёт до получателя при переводе amount
+pub fn amount_after_transfer_fee(transfer_fee: Option<&TransferFee>, amount: u64) -> Result<u64> {
+    match transfer_fee {
+        Some(transfer_fee) => transfer_fee
+            .calculate_post_fee_amount(amount)
+            .ok_or(ErrorCode::Overflow.into()),
+        None => Ok(amount),
+    }
+}
+
+/// Сколько нужно перевести, чтобы получатель получил post_fee_amount
+pub fn amount_before_transfer_fee(
+    transfer_fee: Option<&TransferFee>,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    match transfer_fee {
+        Some(transfer_fee) => transfer_fee
+            .calculate_pre_fee_amount(post_fee_amount)
+            .ok_or(ErrorCode::Overflow.into()),
+        None => Ok(post_fee_amount),
+    }
+}
+
+#[cfg(test)]
+mod tests_transfer_fee {
+    use super::*;
+
+    fn transfer_fee(basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+
+    #[test]
+    fn test_no_fee() {
+        assert_eq!(amount_after_transfer_fee(None, 1_000).unwrap(), 1_000);
+        assert_eq!(amount_before_transfer_fee(None, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_fee_both_ways() {
+        let fee = transfer_fee(100, u64::MAX);
+
+        // 1% комиссии, округление вверх
+        assert_eq!(amount_after_transfer_fee(Some(&fee), 1_000).unwrap(), 990);
+        assert_eq!(amount_after_transfer_fee(Some(&fee), 1_101).unwrap(), 1_089);
+
+        let pre = amount_before_transfer_fee(Some(&fee), 990).unwrap();
+        assert_eq!(amount_after_transfer_fee(Some(&fee), pre).unwrap(), 990);
+    }
+
+    #[test]
+    fn test_maximum_fee() {
+        let fee = transfer_fee(100, 5);
+
+        assert_eq!(
+            amount_after_transfer_fee(Some(&fee), 1_000_000).unwrap(),
+            999_995
+        );
+        assert_eq!(
+            amount_before_transfer_fee(Some(&fee), 999_995).unwrap(),
+            1_000_000
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::commons::{create_ata_if_missing, get_ata_balance, invoke_transfer, SwapAuthority};
+use crate::error::ErrorCode;
+use crate::state::CommonAccounts;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token::spl_token;
+
+/// Режим wrap_sol: source - WSOL ATA пользователя, свопы подписывает сам пользователь
+pub fn check_wrap_sol_allowed(
+    common: &CommonAccounts,
+    authority: &SwapAuthority<'_, '_>,
+) -> Result<()> {
+    require_keys_eq!(
+        common.user_source_token_mint.key(),
+        spl_token::native_mint::ID,
+        ErrorCode::WrapSolRequiresNativeMint
+    );
+    require!(
+        matches!(authority, SwapAuthority::User(_)),
+        ErrorCode::WrapSolRequiresUserAuthority
+    );
+
+    Ok(())
+}
+
+/// Лампорты пользователя вместе с WSOL ATA (сумма и рента возвращаются при закрытии)
+pub fn get_native_balance(common: &CommonAccounts) -> u64 {
+    common
+        .user
+        .lamports()
+        .saturating_add(common.user_source_token_account.lamports())
+}
+
+/// Создаёт WSOL ATA при необходимости и доводит его баланс до amount
+pub fn wrap_sol<'info>(
+    common: &CommonAccounts<'info>,
+    authority: &SwapAuthority<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    let token_program = common.token_program.to_account_info();
+    let source = &common.user_source_token_account;
+
+    let created = create_ata_if_missing(
+        &common.user,
+        &authority.to_account_info(),
+        source,
+        &common.user_source_token_mint,
+        &common.system_program,
+        &token_program,
+        &common.associated_token_program,
+    )?;
+
+    let wrapped = if created { 0 } else { get_ata_balance(source)? };
+    let needed = amount.saturating_sub(wrapped);
+    if needed == 0 {
+        return Ok(());
+    }
+
+    invoke_transfer(&common.user, source, &common.system_program, needed)?;
+    sync_native(&token_program, source)
+}
+
+/// Закрывает WSOL ATA пользователя: весь WSOL и рента возвращаются лампортами
+pub fn unwrap_sol<'info>(common: &CommonAccounts<'info>) -> Result<()> {
+    let token_program = common.token_program.to_account_info();
+    let source = &common.user_source_token_account;
+
+    let ix = spl_token::instruction::close_account(
+        token_program.key,
+        source.key,
+        common.user.key,
+        common.user.key,
+        &[],
+    )?;
+
+    invoke(
+        &ix,
+        &[
+            source.clone(),
+            common.user.to_account_info(),
+            common.user.to_account_info(),
+            token_program,
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Переводит лампорты пользователя на WSOL токен-аккаунт и синхронизирует его баланс
+pub fn transfer_lamports_to_wsol<'info>(
+    common: &CommonAccounts<'info>,
+    destination: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    invoke_transfer(&common.user, destination, &common.system_program, amount)?;
+    sync_native(&common.token_program.to_account_info(), destination)
+}
+
+fn sync_native<'info>(
+    token_program: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+) -> Result<()> {
+    let ix = spl_token::instruction::sync_native(token_program.key, token_account.key)?;
+
+    invoke(&ix, &[token_account.clone(), token_program.clone()])?;
+
+    Ok(())
+}
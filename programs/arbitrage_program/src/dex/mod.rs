@@ -3,3 +3,9 @@ pub mod raydium_amm;
 
 pub mod structures;
 pub use structures::*;
+
+pub mod program_ids;
+pub use program_ids::*;
+
+pub mod pool_factory;
+pub use pool_factory::*;
@@ -0,0 +1,225 @@
+use std::collections::BTreeSet;
+
+use crate::arbitrage_engine::{BasePool, PoolKind, PumpswapAccountsMode};
+use crate::commons::{resolve_token_program, SwapAuthority};
+use crate::dex::pumpswap::{
+    pumpswap_pool::PumpswapPool, PfAmmPool, PumpswapDerivedAccounts, PF_AMM_POOL_ACCOUNT_DISCM,
+};
+use crate::dex::raydium_amm::{raydium_amm_pool::RaydiumAmmPool, AMM_INFO_SIZE};
+use crate::dex::{is_pumpswap_program, is_raydium_amm_v4_program};
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Раскладка ноги начинается с [program_id, pool, ...] у всех DEX
+pub const LEG_PROGRAM_INDEX: usize = 0;
+pub const LEG_POOL_INDEX: usize = 1;
+
+/// Число аккаунтов ноги: поля PumpswapAccounts (PumpswapSlimAccounts в режиме Derived) /
+/// RaydiumAmmAccounts
+pub fn leg_account_count(kind: PoolKind, pumpswap_accounts: PumpswapAccountsMode) -> usize {
+    match kind {
+        PoolKind::Pumpswap if pumpswap_accounts == PumpswapAccountsMode::Derived => {
+            PumpswapSlimAccounts::LEN
+        }
+        PoolKind::Pumpswap => PumpswapAccounts::LEN,
+        PoolKind::RaydiumAmmV4 => RaydiumAmmAccounts::LEN,
+    }
+}
+
+/// Определяет DEX пула по программе-владельцу и дискриминатору (или размеру) данных
+pub fn detect_pool_kind(pool: &AccountInfo) -> Result<PoolKind> {
+    let data = pool.try_borrow_data()?;
+
+    detect_pool_kind_data(pool.owner, &data)
+}
+
+pub fn detect_pool_kind_data(owner: &Pubkey, data: &[u8]) -> Result<PoolKind> {
+    if is_pumpswap_program(owner) && data.starts_with(&PF_AMM_POOL_ACCOUNT_DISCM) {
+        return Ok(PoolKind::Pumpswap);
+    }
+
+    if is_raydium_amm_v4_program(owner) && data.len() == AMM_INFO_SIZE {
+        return Ok(PoolKind::RaydiumAmmV4);
+    }
+
+    err!(ErrorCode::UnsupportedPoolAccount)
+}
+
+/// Аккаунты одной ноги арбитража, прочитанные из remaining_accounts
+pub enum PoolAccounts<'info> {
+    Pumpswap(Box<PumpswapAccounts<'info>>),
+    // Нога Pumpswap без выведенных аккаунтов, до fill_derived
+    PumpswapSlim(Box<PumpswapSlimAccounts<'info>>),
+    RaydiumAmmV4(Box<RaydiumAmmAccounts<'info>>),
+}
+
+impl<'info> PoolAccounts<'info> {
+    /// Читает аккаунты ноги с начала среза в порядке полей PumpswapAccounts / RaydiumAmmAccounts
+    /// и сдвигает срез за них. DEX определяется по аккаунту пула; expected_kind, если задан,
+    /// должен с ним совпасть. Constraints те же, что в именованных инструкциях.
+    pub fn try_from_remaining(
+        expected_kind: Option<PoolKind>,
+        pumpswap_accounts: PumpswapAccountsMode,
+        program_id: &Pubkey,
+        accounts: &mut &'info [AccountInfo<'info>],
+    ) -> Result<Self> {
+        let pool = accounts
+            .get(LEG_POOL_INDEX)
+            .ok_or(ErrorCode::PoolLegAccountsMissing)?;
+        let kind = detect_pool_kind(pool)?;
+
+        if let Some(expected_kind) = expected_kind {
+            require!(expected_kind == kind, ErrorCode::PoolKindMismatch);
+        }
+        require!(
+            accounts.len() >= leg_account_count(kind, pumpswap_accounts),
+            ErrorCode::PoolLegAccountsMissing
+        );
+        require_keys_eq!(
+            accounts[LEG_PROGRAM_INDEX].key(),
+            *pool.owner,
+            ErrorCode::InvalidAccountOwner
+        );
+
+        let mut reallocs = BTreeSet::new();
+
+        match kind {
+            PoolKind::Pumpswap if pumpswap_accounts == PumpswapAccountsMode::Derived => Ok(
+                PoolAccounts::PumpswapSlim(Box::new(PumpswapSlimAccounts::try_accounts(
+                    program_id,
+                    accounts,
+                    &[],
+                    &mut Default::default(),
+                    &mut reallocs,
+                )?)),
+            ),
+            PoolKind::Pumpswap => Ok(PoolAccounts::Pumpswap(Box::new(
+                PumpswapAccounts::try_accounts(
+                    program_id,
+                    accounts,
+                    &[],
+                    &mut Default::default(),
+                    &mut reallocs,
+                )?,
+            ))),
+            PoolKind::RaydiumAmmV4 => Ok(PoolAccounts::RaydiumAmmV4(Box::new(
+                RaydiumAmmAccounts::try_accounts(
+                    program_id,
+                    accounts,
+                    &[],
+                    &mut Default::default(),
+                    &mut reallocs,
+                )?,
+            ))),
+        }
+    }
+
+    /// Дополняет slim-ногу Pumpswap до PumpswapAccounts: PDA выводятся из пула и подписанта user,
+    /// а сами аккаунты ищутся в derived_accounts (хвост remaining_accounts после всех ног)
+    pub fn fill_derived(
+        &mut self,
+        common: &CommonAccounts<'info>,
+        user: &Pubkey,
+        derived_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        if let PoolAccounts::PumpswapSlim(slim) = self {
+            let coin_creator = PfAmmPool::deserialize_coin_creator(&slim.pool.try_borrow_data()?)?;
+            let quote_token_program = resolve_token_program(&slim.quote_mint, common)?;
+
+            let accounts = PumpswapDerivedAccounts::derive(
+                slim.program_id.key,
+                slim.fee_program.key,
+                &coin_creator,
+                slim.quote_mint.key,
+                quote_token_program.key,
+                user,
+            )
+            .fill(slim, derived_accounts)?;
+
+            *self = PoolAccounts::Pumpswap(Box::new(accounts));
+        }
+
+        Ok(())
+    }
+
+    /// Фабрика пулов: новый DEX добавляется сюда и сразу доступен во всех парах two_pool_arb
+    pub fn build_pool<'b>(
+        &'b self,
+        common: &'b CommonAccounts<'info>,
+        authority: SwapAuthority<'b, 'info>,
+        verify_pumpswap_pdas: bool,
+    ) -> Result<Box<dyn BasePool + 'b>> {
+        match self {
+            PoolAccounts::Pumpswap(accounts) => Ok(Box::new(PumpswapPool::new(
+                common,
+                accounts,
+                authority,
+                verify_pumpswap_pdas,
+            )?)),
+            PoolAccounts::PumpswapSlim(_) => err!(ErrorCode::PumpswapDerivedAccountMissing),
+            PoolAccounts::RaydiumAmmV4(accounts) => {
+                Ok(Box::new(RaydiumAmmPool::new(common, accounts, authority)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_pool_factory {
+    use super::*;
+    use crate::dex::pumpswap::GLOBAL_CONFIG_ACCOUNT_DISCM;
+    use crate::dex::{PUMPSWAP_PROGRAM_ID, RAYDIUM_AMM_V4_PROGRAM_ID};
+
+    fn pumpswap_pool_data(discriminator: [u8; 8]) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.resize(8 + 203, 0);
+        data
+    }
+
+    #[test]
+    fn test_detect_pool_kind() {
+        assert_eq!(
+            detect_pool_kind_data(
+                &PUMPSWAP_PROGRAM_ID,
+                &pumpswap_pool_data(PF_AMM_POOL_ACCOUNT_DISCM)
+            )
+            .unwrap(),
+            PoolKind::Pumpswap
+        );
+        assert_eq!(
+            detect_pool_kind_data(&RAYDIUM_AMM_V4_PROGRAM_ID, &[0; AMM_INFO_SIZE]).unwrap(),
+            PoolKind::RaydiumAmmV4
+        );
+
+        // AMM_INFO_SIZE совпадает с раскладкой AmmInfo
+        assert!(crate::dex::raydium_amm::AmmInfo::deserialize_data(&[0; AMM_INFO_SIZE]).is_ok());
+    }
+
+    #[test]
+    fn test_detect_wrong_pool() {
+        // GlobalConfig Pumpswap вместо пула
+        assert_eq!(
+            detect_pool_kind_data(
+                &PUMPSWAP_PROGRAM_ID,
+                &pumpswap_pool_data(GLOBAL_CONFIG_ACCOUNT_DISCM)
+            )
+            .unwrap_err(),
+            ErrorCode::UnsupportedPoolAccount.into()
+        );
+        // не AmmInfo по размеру
+        assert_eq!(
+            detect_pool_kind_data(&RAYDIUM_AMM_V4_PROGRAM_ID, &[0; 100]).unwrap_err(),
+            ErrorCode::UnsupportedPoolAccount.into()
+        );
+        // пул Pumpswap под чужим владельцем
+        assert_eq!(
+            detect_pool_kind_data(
+                &Pubkey::new_unique(),
+                &pumpswap_pool_data(PF_AMM_POOL_ACCOUNT_DISCM)
+            )
+            .unwrap_err(),
+            ErrorCode::UnsupportedPoolAccount.into()
+        );
+    }
+}
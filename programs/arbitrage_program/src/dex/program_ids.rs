@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+pub const PUMPSWAP_PROGRAM_ID: Pubkey = pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+// pump.fun bonding curve: его PDA pool-authority - creator канонических пулов Pumpswap
+pub const PUMP_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+pub const PUMPSWAP_FEE_PROGRAM_ID: Pubkey = pubkey!("pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ");
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey =
+    pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const KAMINO_LENDING_PROGRAM_ID: Pubkey =
+    pubkey!("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
+
+// Дополнительные разрешённые program id для devnet и локальных моков.
+// Pumpswap и fee program на devnet развёрнуты по тем же адресам, что и на mainnet;
+// локальные моки нужно либо деплоить по этим адресам, либо добавлять сюда.
+#[cfg(feature = "dex-program-overrides")]
+pub const PUMPSWAP_PROGRAM_ID_OVERRIDES: &[Pubkey] = &[];
+#[cfg(feature = "dex-program-overrides")]
+pub const PUMPSWAP_FEE_PROGRAM_ID_OVERRIDES: &[Pubkey] = &[];
+#[cfg(feature = "dex-program-overrides")]
+pub const RAYDIUM_AMM_V4_PROGRAM_ID_OVERRIDES: &[Pubkey] =
+    &[pubkey!("DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav")];
+// programs/mock_lending
+#[cfg(feature = "dex-program-overrides")]
+pub const FLASH_LOAN_PROGRAM_ID_OVERRIDES: &[Pubkey] =
+    &[pubkey!("EDZeKHZy8X5HGnXqwR7JnLV7M92UJ6FGPyoTkhfKo9a8")];
+
+#[cfg(not(feature = "dex-program-overrides"))]
+pub const PUMPSWAP_PROGRAM_ID_OVERRIDES: &[Pubkey] = &[];
+#[cfg(not(feature = "dex-program-overrides"))]
+pub const PUMPSWAP_FEE_PROGRAM_ID_OVERRIDES: &[Pubkey] = &[];
+#[cfg(not(feature = "dex-program-overrides"))]
+pub const RAYDIUM_AMM_V4_PROGRAM_ID_OVERRIDES: &[Pubkey] = &[];
+#[cfg(not(feature = "dex-program-overrides"))]
+pub const FLASH_LOAN_PROGRAM_ID_OVERRIDES: &[Pubkey] = &[];
+
+pub fn is_pumpswap_program(key: &Pubkey) -> bool {
+    *key == PUMPSWAP_PROGRAM_ID || PUMPSWAP_PROGRAM_ID_OVERRIDES.contains(key)
+}
+
+pub fn is_pumpswap_fee_program(key: &Pubkey) -> bool {
+    *key == PUMPSWAP_FEE_PROGRAM_ID || PUMPSWAP_FEE_PROGRAM_ID_OVERRIDES.contains(key)
+}
+
+pub fn is_raydium_amm_v4_program(key: &Pubkey) -> bool {
+    *key == RAYDIUM_AMM_V4_PROGRAM_ID || RAYDIUM_AMM_V4_PROGRAM_ID_OVERRIDES.contains(key)
+}
+
+pub fn is_flash_loan_program(key: &Pubkey) -> bool {
+    *key == KAMINO_LENDING_PROGRAM_ID || FLASH_LOAN_PROGRAM_ID_OVERRIDES.contains(key)
+}
@@ -1,34 +1,109 @@
 use crate::dex::pumpswap::interfaces::accounts::{FeeConfig, FeeTier, Fees};
+use crate::dex::PUMP_PROGRAM_ID;
 use crate::error::ErrorCode;
 use crate::Result;
+use anchor_lang::prelude::Pubkey;
+
+pub const PUMP_POOL_AUTHORITY_SEED: &[u8] = b"pool-authority";
 
 pub fn pool_market_cap(
     base_mint_supply: u64,
-    base_reserve: u64,
-    quote_reserve: u64,
+    base_reserve: u128,
+    quote_reserve: u128,
 ) -> Result<u128> {
     if base_reserve == 0 {
         return Err(ErrorCode::InvalidAccount.into());
     }
 
-    let market_cap = (quote_reserve as u128)
+    let market_cap = quote_reserve
         .checked_mul(base_mint_supply as u128)
         .ok_or(ErrorCode::InvalidAccount)?
-        .checked_div(base_reserve as u128)
+        .checked_div(base_reserve)
         .ok_or(ErrorCode::InvalidAccount)?;
 
     Ok(market_cap)
 }
 
-pub fn compute_fees_bps(
-    fee_config: FeeConfig,
-    base_mint_supply: u64,
-    base_reserve: u64,
-    quote_reserve: u64,
-) -> Result<Fees> {
-    let market_cap = pool_market_cap(base_mint_supply, base_reserve, quote_reserve)?;
+/// Пул создан миграцией pump.fun: creator пула - PDA pool-authority программы pump для base минта.
+/// find_program_address стоит ~1500 CU за каждую попытку bump (обычно 1-2 попытки)
+pub fn is_pump_pool(base_mint: &Pubkey, pool_creator: &Pubkey) -> bool {
+    let (pool_authority, _) = Pubkey::find_program_address(
+        &[PUMP_POOL_AUTHORITY_SEED, base_mint.as_ref()],
+        &PUMP_PROGRAM_ID,
+    );
+
+    pool_authority == *pool_creator
+}
+
+/// Комиссии пула из FeeConfig: тиры по market cap только для pump-пулов, остальным flat_fees.
+/// Pumpswap выбирает тир по резервам до сделки.
+#[derive(Debug, Clone)]
+pub enum FeeSchedule {
+    Flat(Fees),
+    Tiered {
+        fee_tiers: Vec<FeeTier>,
+        base_mint_supply: u64,
+    },
+}
+
+impl FeeSchedule {
+    /// is_pump_pool вызывается, только если в FeeConfig есть тиры: без них всем пулам
+    /// достаются flat_fees и PDA pool-authority не нужен
+    pub fn new(
+        fee_config: FeeConfig,
+        is_pump_pool: impl FnOnce() -> bool,
+        base_mint_supply: u64,
+    ) -> Self {
+        if !fee_config.fee_tiers.is_empty() && is_pump_pool() {
+            FeeSchedule::Tiered {
+                fee_tiers: fee_config.fee_tiers,
+                base_mint_supply,
+            }
+        } else {
+            FeeSchedule::Flat(fee_config.flat_fees)
+        }
+    }
+
+    pub fn fees(&self, base_reserve: u64, quote_reserve: u64) -> Result<Fees> {
+        match self {
+            FeeSchedule::Flat(fees) => Ok(*fees),
+            FeeSchedule::Tiered {
+                fee_tiers,
+                base_mint_supply,
+            } => calculate_fee_tier(
+                fee_tiers,
+                pool_market_cap(
+                    *base_mint_supply,
+                    base_reserve as u128,
+                    quote_reserve as u128,
+                )?,
+            ),
+        }
+    }
 
-    calculate_fee_tier(&fee_config.fee_tiers, market_cap)
+    /// Попадёт ли пул с резервами после сделки new_reserves (base, quote) в другой тир.
+    /// Сама сделка оплачивается по тиру до неё, флаг нужен для следующих котировок.
+    pub fn crosses_tier(
+        &self,
+        base_reserve: u64,
+        quote_reserve: u64,
+        new_reserves: (u128, u128),
+    ) -> Result<bool> {
+        match self {
+            FeeSchedule::Flat(_) => Ok(false),
+            FeeSchedule::Tiered {
+                fee_tiers,
+                base_mint_supply,
+            } => {
+                let (new_base_reserve, new_quote_reserve) = new_reserves;
+                let market_cap =
+                    pool_market_cap(*base_mint_supply, new_base_reserve, new_quote_reserve)?;
+
+                Ok(calculate_fee_tier(fee_tiers, market_cap)?
+                    != self.fees(base_reserve, quote_reserve)?)
+            }
+        }
+    }
 }
 
 fn calculate_fee_tier(fee_tiers: &[FeeTier], market_cap: u128) -> Result<Fees> {
@@ -50,3 +125,159 @@ fn calculate_fee_tier(fee_tiers: &[FeeTier], market_cap: u128) -> Result<Fees> {
 
     Ok(first_tier.fees)
 }
+
+#[cfg(test)]
+mod tests_fee_schedule {
+    use super::*;
+    use crate::dex::pumpswap::get_amount_out;
+
+    const SUPPLY: u64 = 1_000_000_000_000_000;
+    const BASE_RESERVE: u64 = 200_000_000_000_000;
+    // market cap 420 SOL
+    const QUOTE_RESERVE: u64 = 84_000_000_000;
+
+    const TIER_LOW: Fees = Fees {
+        lp_fee_bps: 20,
+        protocol_fee_bps: 5,
+        creator_fee_bps: 95,
+    };
+    const TIER_HIGH: Fees = Fees {
+        lp_fee_bps: 20,
+        protocol_fee_bps: 5,
+        creator_fee_bps: 30,
+    };
+    const FLAT: Fees = Fees {
+        lp_fee_bps: 25,
+        protocol_fee_bps: 5,
+        creator_fee_bps: 0,
+    };
+
+    fn fee_config() -> FeeConfig {
+        FeeConfig {
+            bump: 255,
+            admin: Pubkey::new_unique(),
+            flat_fees: FLAT,
+            fee_tiers: vec![
+                FeeTier {
+                    market_cap_lamports_threshold: 0,
+                    fees: TIER_LOW,
+                },
+                // порог 500 SOL
+                FeeTier {
+                    market_cap_lamports_threshold: 500_000_000_000,
+                    fees: TIER_HIGH,
+                },
+            ],
+        }
+    }
+
+    fn buy_crosses(schedule: &FeeSchedule, quote_in: u64) -> bool {
+        let fees = schedule.fees(BASE_RESERVE, QUOTE_RESERVE).unwrap();
+        let result = get_amount_out(BASE_RESERVE, QUOTE_RESERVE, quote_in, &fees, false).unwrap();
+
+        schedule
+            .crosses_tier(BASE_RESERVE, QUOTE_RESERVE, result.new_reserves)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tier_by_market_cap() {
+        let schedule = FeeSchedule::new(fee_config(), || true, SUPPLY);
+
+        assert_eq!(
+            schedule.fees(BASE_RESERVE, QUOTE_RESERVE).unwrap(),
+            TIER_LOW
+        );
+        assert_eq!(
+            schedule.fees(BASE_RESERVE, 100_000_000_000).unwrap(),
+            TIER_HIGH
+        );
+        // ровно на пороге - уже верхний тир
+        assert_eq!(
+            schedule.fees(BASE_RESERVE, 100_000_000_000 - 1).unwrap(),
+            TIER_LOW
+        );
+    }
+
+    #[test]
+    fn test_buy_crosses_tier() {
+        let schedule = FeeSchedule::new(fee_config(), || true, SUPPLY);
+
+        // 1 SOL не доводит market cap до 500 SOL
+        assert!(!buy_crosses(&schedule, 1_000_000_000));
+        // ~8.5 SOL поднимают цену выше порога
+        assert!(buy_crosses(&schedule, 10_000_000_000));
+    }
+
+    #[test]
+    fn test_sell_crosses_tier_down() {
+        let schedule = FeeSchedule::new(fee_config(), || true, SUPPLY);
+        // market cap 510 SOL, верхний тир
+        let quote_reserve = 102_000_000_000;
+        let fees = schedule.fees(BASE_RESERVE, quote_reserve).unwrap();
+        assert_eq!(fees, TIER_HIGH);
+
+        let small =
+            get_amount_out(BASE_RESERVE, quote_reserve, 1_000_000_000, &fees, true).unwrap();
+        assert!(!schedule
+            .crosses_tier(BASE_RESERVE, quote_reserve, small.new_reserves)
+            .unwrap());
+
+        let large =
+            get_amount_out(BASE_RESERVE, quote_reserve, 10_000_000_000_000, &fees, true).unwrap();
+        assert!(schedule
+            .crosses_tier(BASE_RESERVE, quote_reserve, large.new_reserves)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_crosses_tier_at_threshold() {
+        let schedule = FeeSchedule::new(fee_config(), || true, SUPPLY);
+        let base_reserve = BASE_RESERVE as u128;
+
+        // market cap после сделки ровно 500 SOL - уже верхний тир, на лампорт quote меньше - нет
+        assert!(schedule
+            .crosses_tier(BASE_RESERVE, QUOTE_RESERVE, (base_reserve, 100_000_000_000))
+            .unwrap());
+        assert!(!schedule
+            .crosses_tier(
+                BASE_RESERVE,
+                QUOTE_RESERVE,
+                (base_reserve, 100_000_000_000 - 1)
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn test_no_tiers_skips_pump_check() {
+        let fee_config = FeeConfig {
+            fee_tiers: vec![],
+            ..fee_config()
+        };
+        let schedule = FeeSchedule::new(fee_config, || panic!("pool-authority derived"), SUPPLY);
+
+        assert_eq!(schedule.fees(BASE_RESERVE, QUOTE_RESERVE).unwrap(), FLAT);
+    }
+
+    #[test]
+    fn test_flat_fees() {
+        let schedule = FeeSchedule::new(fee_config(), || false, SUPPLY);
+
+        assert_eq!(schedule.fees(BASE_RESERVE, QUOTE_RESERVE).unwrap(), FLAT);
+        assert_eq!(schedule.fees(BASE_RESERVE, 100_000_000_000).unwrap(), FLAT);
+        assert!(!buy_crosses(&schedule, 10_000_000_000));
+    }
+
+    #[test]
+    fn test_is_pump_pool() {
+        let base_mint = Pubkey::new_unique();
+        let (pool_authority, _) = Pubkey::find_program_address(
+            &[PUMP_POOL_AUTHORITY_SEED, base_mint.as_ref()],
+            &PUMP_PROGRAM_ID,
+        );
+
+        assert!(is_pump_pool(&base_mint, &pool_authority));
+        assert!(!is_pump_pool(&base_mint, &Pubkey::new_unique()));
+        assert!(!is_pump_pool(&Pubkey::new_unique(), &pool_authority));
+    }
+}
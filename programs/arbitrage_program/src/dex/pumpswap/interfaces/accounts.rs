@@ -2,6 +2,8 @@ use crate::{error::ErrorCode, Pubkey, Result};
 use anchor_lang::prelude::{borsh::BorshDeserialize, *};
 
 pub const GLOBAL_CONFIG_ACCOUNT_DISCM: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+pub const DISABLE_BUY_FLAG: u8 = 1 << 3;
+pub const DISABLE_SELL_FLAG: u8 = 1 << 4;
 #[derive(Debug, Clone, Copy, BorshDeserialize)]
 pub struct GlobalConfig {
     /// The admin pubkey
@@ -40,6 +42,24 @@ impl GlobalConfig {
         Ok(result)
     }
 
+    pub fn deserialize_disable_flags(data: &[u8]) -> Result<u8> {
+        if data.len() < 8 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let maybe_discm: [u8; 8] = data[0..8].try_into().unwrap();
+        if maybe_discm != GLOBAL_CONFIG_ACCOUNT_DISCM {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        // admin + lp_fee_basis_points + protocol_fee_basis_points
+        let disable_flags_offset = 8 + 32 + 8 + 8;
+
+        data.get(disable_flags_offset)
+            .copied()
+            .ok_or(ErrorCode::InvalidAccount.into())
+    }
+
     pub fn deserialize_fees(data: &[u8]) -> Result<(u64, u64, u64)> {
         if data.len() < 8 {
             return Err(ErrorCode::InvalidAccount.into());
@@ -61,7 +81,7 @@ impl GlobalConfig {
                 .map_err(|_| error!(ErrorCode::InvalidAccount))?,
         );
 
-        offset = offset + 8;
+        offset += 8;
 
         // protocol_fee_basis_points: u64 (8 bytes)
         let protocol_fee_basis_points = u64::from_le_bytes(
@@ -149,9 +169,89 @@ impl PfAmmPool {
 
         Ok((base_mint, quote_mint))
     }
+
+    pub fn deserialize_token_accounts(data: &[u8]) -> Result<(Pubkey, Pubkey)> {
+        if data.len() < 8 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let maybe_discm: [u8; 8] = data[0..8].try_into().unwrap();
+        if maybe_discm != PF_AMM_POOL_ACCOUNT_DISCM {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        // bump + index + creator + base_mint + quote_mint + lp_mint
+        let pool_base_token_account_offset = 8 + 3 + 32 * 4;
+        let pool_quote_token_account_offset = pool_base_token_account_offset + 32;
+
+        if data.len() < pool_quote_token_account_offset + 32 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let pool_base_token_account = Pubkey::new_from_array(
+            data[pool_base_token_account_offset..pool_base_token_account_offset + 32]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+        let pool_quote_token_account = Pubkey::new_from_array(
+            data[pool_quote_token_account_offset..pool_quote_token_account_offset + 32]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+
+        Ok((pool_base_token_account, pool_quote_token_account))
+    }
+
+    pub fn deserialize_creator(data: &[u8]) -> Result<Pubkey> {
+        if data.len() < 8 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let maybe_discm: [u8; 8] = data[0..8].try_into().unwrap();
+        if maybe_discm != PF_AMM_POOL_ACCOUNT_DISCM {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        // bump + index
+        let creator_offset = 8 + 3;
+
+        if data.len() < creator_offset + 32 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        Ok(Pubkey::new_from_array(
+            data[creator_offset..creator_offset + 32]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        ))
+    }
+
+    pub fn deserialize_coin_creator(data: &[u8]) -> Result<Pubkey> {
+        if data.len() < 8 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let maybe_discm: [u8; 8] = data[0..8].try_into().unwrap();
+        if maybe_discm != PF_AMM_POOL_ACCOUNT_DISCM {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        // bump + index + 6 ключей + lp_supply
+        let coin_creator_offset = 8 + 3 + 32 * 6 + 8;
+
+        if data.len() < coin_creator_offset + 32 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        Ok(Pubkey::new_from_array(
+            data[coin_creator_offset..coin_creator_offset + 32]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        ))
+    }
 }
 
-#[derive(Debug, Clone, Copy, BorshDeserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize)]
 pub struct Fees {
     pub lp_fee_bps: u64,
     pub protocol_fee_bps: u64,
@@ -1,13 +1,14 @@
+use crate::commons::SwapAuthority;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
-use anchor_lang::solana_program::program::invoke;
 
 /// Executes a PumpSwap buy operation
 /// This function buys base tokens using quote tokens
+#[allow(clippy::too_many_arguments)]
 pub fn pumpswap_buy<'info>(
     program: &AccountInfo<'info>,
     pool: &AccountInfo<'info>,
-    user: &Signer<'info>,
+    user: &SwapAuthority<'_, 'info>,
     global_config: &AccountInfo<'info>,
     base_mint: &AccountInfo<'info>,
     quote_mint: &AccountInfo<'info>,
@@ -78,7 +79,7 @@ pub fn pumpswap_buy<'info>(
     let account_infos = &[
         program.clone(),
         pool.clone(),
-        user.to_account_info().clone(),
+        user.to_account_info(),
         global_config.clone(),
         base_mint.clone(),
         quote_mint.clone(),
@@ -103,17 +104,18 @@ pub fn pumpswap_buy<'info>(
     ];
 
     // Выполняем инструкцию
-    invoke(&ix, account_infos)?;
+    user.invoke(&ix, account_infos)?;
 
     Ok(())
 }
 
 /// Executes a PumpSwap sell operation
 /// This function sells base tokens for quote tokens
+#[allow(clippy::too_many_arguments)]
 pub fn pumpswap_sell<'info>(
     program: &AccountInfo<'info>,
     pool: &AccountInfo<'info>,
-    user: &Signer<'info>,
+    user: &SwapAuthority<'_, 'info>,
     global_config: &AccountInfo<'info>,
     base_mint: &AccountInfo<'info>,
     quote_mint: &AccountInfo<'info>,
@@ -180,7 +182,7 @@ pub fn pumpswap_sell<'info>(
     let account_infos = &[
         program.clone(),
         pool.clone(),
-        user.to_account_info().clone(),
+        user.to_account_info(),
         global_config.clone(),
         base_mint.clone(),
         quote_mint.clone(),
@@ -203,7 +205,7 @@ pub fn pumpswap_sell<'info>(
     ];
 
     // Выполняем инструкцию
-    invoke(&ix, account_infos)?;
+    user.invoke(&ix, account_infos)?;
 
     Ok(())
 }
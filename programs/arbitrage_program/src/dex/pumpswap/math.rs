@@ -1,258 +1,495 @@
+use crate::dex::pumpswap::Fees;
 use crate::dex::{AmountInResult, AmountOutResult};
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
 
-pub fn get_amount_out(
+// Целочисленная математика Pumpswap по формулам и округлениям инструкций pAMM.
+// Каждая комиссия (lp, protocol, creator) считается отдельно с округлением вверх.
+// С пойманными mainnet свапами не сверена: векторы в test_data посчитаны по тем же формулам
+// и ловят только регрессии
+
+const FEE_DENOMINATOR: u128 = 10_000;
+
+/// Комиссия с округлением вверх
+pub fn fee(amount: u128, fee_bps: u64) -> u128 {
+    (amount * fee_bps as u128).div_ceil(FEE_DENOMINATOR)
+}
+
+fn total_fee(amount: u128, fees: &Fees) -> u128 {
+    fee(amount, fees.lp_fee_bps)
+        + fee(amount, fees.protocol_fee_bps)
+        + fee(amount, fees.creator_fee_bps)
+}
+
+fn total_fee_bps(fees: &Fees) -> u128 {
+    fees.lp_fee_bps as u128 + fees.protocol_fee_bps as u128 + fees.creator_fee_bps as u128
+}
+
+fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| ErrorCode::Overflow.into())
+}
+
+/// sell: сколько quote получит пользователь за base_amount_in (комиссии берутся из выхода)
+pub fn sell_base_input(
+    base_amount_in: u64,
     base_reserve: u64,
     quote_reserve: u64,
-    amount_in: u64,
-    fee_multiplier: &[u64],
-    swap_for_quote: bool,
-) -> AmountOutResult {
-    // Определяем направление свапа
-    let (input_reserve, output_reserve) = if swap_for_quote {
-        (base_reserve, quote_reserve)
-    } else {
-        (quote_reserve, base_reserve)
-    };
-
-    let input_reserve_f64 = input_reserve as f64;
-    let output_reserve_f64 = output_reserve as f64;
-    let amount_in_f64 = amount_in as f64;
+    fees: &Fees,
+) -> Result<u64> {
+    let raw_quote_out = sell_raw_quote_out(base_amount_in, base_reserve, quote_reserve);
 
-    let amount_out: u64;
-    let new_price: f64;
+    // комиссия больше выхода - получать нечего
+    to_u64(raw_quote_out.saturating_sub(total_fee(raw_quote_out, fees)))
+}
 
-    if swap_for_quote {
-        let numerator = output_reserve_f64 * amount_in_f64;
-        let denominator = input_reserve_f64 + amount_in_f64;
-        let raw_amount_out = numerator / denominator;
+fn sell_raw_quote_out(base_amount_in: u64, base_reserve: u64, quote_reserve: u64) -> u128 {
+    quote_reserve as u128 * base_amount_in as u128 / (base_reserve as u128 + base_amount_in as u128)
+}
 
-        let mut total_fee: u64 = 0;
-        for fee in fee_multiplier {
-            let fee_amount = ((raw_amount_out * (*fee as f64) / 10000.0).ceil()) as u64;
-            total_fee += fee_amount;
-        }
+/// buy: сколько quote спишет программа за ровно base_amount_out (комиссии сверху)
+pub fn buy_base_output(
+    base_amount_out: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &Fees,
+) -> Result<u64> {
+    require!(
+        base_amount_out < base_reserve,
+        ErrorCode::OutputExceedsReserve
+    );
 
-        amount_out = (raw_amount_out as u64).checked_sub(total_fee).unwrap_or(1);
+    let raw_quote_in = (quote_reserve as u128 * base_amount_out as u128)
+        .div_ceil((base_reserve - base_amount_out) as u128);
 
-        // Обновленные резервы (используем raw_amount_out)
-        let new_input_reserve = input_reserve_f64 + amount_in_f64;
-        let new_output_reserve = output_reserve_f64 - raw_amount_out;
+    to_u64(raw_quote_in + total_fee(raw_quote_in, fees))
+}
 
-        new_price = new_output_reserve / new_input_reserve;
-    } else {
-        let mut total_fee_rate: u64 = 0;
-        for fee in fee_multiplier {
-            total_fee_rate += fee;
-        }
+/// buy_exact_quote_in: сколько base программа выдаст за spendable_quote_in
+pub fn buy_exact_quote_in(
+    spendable_quote_in: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &Fees,
+) -> Result<u64> {
+    let effective_quote_in =
+        spendable_quote_in as u128 * FEE_DENOMINATOR / (FEE_DENOMINATOR + total_fee_bps(fees));
 
-        let fee_numerator = amount_in as f64;
-        let fee_denominator = 1.0 + (total_fee_rate as f64 + 0.0001) / 10000.0;
+    to_u64(base_reserve as u128 * effective_quote_in / (quote_reserve as u128 + effective_quote_in))
+}
 
-        let net_amount_in = (fee_numerator / fee_denominator).floor();
+/// Максимальный base_amount_out для buy, стоимость которого не превышает quote_amount_in.
+/// Возвращает (base_amount_out, фактическая стоимость)
+pub fn buy_quote_input(
+    quote_amount_in: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &Fees,
+) -> Result<(u64, u64)> {
+    let quote_amount_in = quote_amount_in as u128;
+    let with_fee = |raw: u128| raw + total_fee(raw, fees);
+
+    // Наибольший raw_quote_in, который вместе с комиссиями укладывается в quote_amount_in.
+    // Оценка снизу отличается от ответа не больше чем на число компонент комиссии.
+    let mut raw_quote_in =
+        quote_amount_in * FEE_DENOMINATOR / (FEE_DENOMINATOR + total_fee_bps(fees));
+    while raw_quote_in > 0 && with_fee(raw_quote_in) > quote_amount_in {
+        raw_quote_in -= 1;
+    }
+    while with_fee(raw_quote_in + 1) <= quote_amount_in {
+        raw_quote_in += 1;
+    }
 
-        let numerator = output_reserve_f64 * net_amount_in;
-        let denominator = input_reserve_f64 + net_amount_in;
+    // ceil(Q * b / (B - b)) <= raw  <=>  b <= raw * B / (Q + raw)
+    let base_amount_out =
+        to_u64(raw_quote_in * base_reserve as u128 / (quote_reserve as u128 + raw_quote_in))?;
+    if base_amount_out == 0 {
+        return Ok((0, 0));
+    }
 
-        amount_out = (numerator / denominator) as u64;
+    let cost = buy_base_output(base_amount_out, base_reserve, quote_reserve, fees)?;
 
-        let new_input_reserve = input_reserve_f64 + net_amount_in;
-        let new_output_reserve = output_reserve_f64 - (amount_out as f64);
+    Ok((base_amount_out, cost))
+}
 
-        new_price = new_output_reserve / new_input_reserve;
+/// Минимальный base_amount_in для sell, после комиссий дающий не меньше quote_amount_out
+pub fn sell_quote_output(
+    quote_amount_out: u64,
+    base_reserve: u64,
+    quote_reserve: u64,
+    fees: &Fees,
+) -> Result<u64> {
+    let fee_bps = total_fee_bps(fees);
+    require!(fee_bps < FEE_DENOMINATOR, ErrorCode::Overflow);
+
+    let quote_amount_out = quote_amount_out as u128;
+    let net = |raw: u128| raw.saturating_sub(total_fee(raw, fees));
+
+    // Наименьший raw_quote_out, который после комиссий даёт quote_amount_out
+    let mut raw_quote_out =
+        (quote_amount_out * FEE_DENOMINATOR).div_ceil(FEE_DENOMINATOR - fee_bps);
+    while net(raw_quote_out) < quote_amount_out {
+        raw_quote_out += 1;
+    }
+    while raw_quote_out > 0 && net(raw_quote_out - 1) >= quote_amount_out {
+        raw_quote_out -= 1;
     }
 
-    AmountOutResult {
+    // выход до комиссии тоже должен быть меньше резерва
+    require!(
+        raw_quote_out < quote_reserve as u128,
+        ErrorCode::OutputExceedsReserve
+    );
+
+    // floor(Q * b / (B + b)) >= raw  <=>  b >= raw * B / (Q - raw)
+    to_u64((raw_quote_out * base_reserve as u128).div_ceil(quote_reserve as u128 - raw_quote_out))
+}
+
+pub fn get_amount_out(
+    base_reserve: u64,
+    quote_reserve: u64,
+    amount_in: u64,
+    fees: &Fees,
+    swap_for_quote: bool,
+) -> Result<AmountOutResult> {
+    require!(
+        base_reserve != 0 && quote_reserve != 0,
+        ErrorCode::ZeroReserve
+    );
+
+    let (amount_out, consumed_in_amount, new_base_reserve, new_quote_reserve) = if swap_for_quote {
+        let raw_quote_out = sell_raw_quote_out(amount_in, base_reserve, quote_reserve);
+        let amount_out = sell_base_input(amount_in, base_reserve, quote_reserve, fees)?;
+
+        (
+            amount_out,
+            amount_in,
+            base_reserve as u128 + amount_in as u128,
+            quote_reserve as u128 - raw_quote_out,
+        )
+    } else {
+        // Покупка исполняется инструкцией buy с точным base на выходе
+        let (amount_out, cost) = buy_quote_input(amount_in, base_reserve, quote_reserve, fees)?;
+
+        (
+            amount_out,
+            cost,
+            (base_reserve - amount_out) as u128,
+            quote_reserve as u128 + cost as u128,
+        )
+    };
+
+    Ok(AmountOutResult {
         amount_out,
-        new_price: if swap_for_quote {
-            new_price
-        } else {
-            1.0 / new_price
-        },
-    }
+        consumed_in_amount,
+        new_price: new_quote_reserve as f64 / new_base_reserve as f64,
+        new_reserves: (new_base_reserve, new_quote_reserve),
+    })
 }
 
 pub fn get_amount_in(
     base_reserve: u64,
     quote_reserve: u64,
     amount_out: u64,
-    fee_multiplier: &[u64],
+    fees: &Fees,
     swap_for_quote: bool,
-) -> AmountInResult {
-    let (input_reserve, output_reserve) = if swap_for_quote {
-        (base_reserve, quote_reserve)
+) -> Result<AmountInResult> {
+    require!(
+        base_reserve != 0 && quote_reserve != 0,
+        ErrorCode::ZeroReserve
+    );
+
+    let (amount_in, new_base_reserve, new_quote_reserve) = if swap_for_quote {
+        require!(amount_out < quote_reserve, ErrorCode::OutputExceedsReserve);
+
+        let amount_in = sell_quote_output(amount_out, base_reserve, quote_reserve, fees)?;
+
+        (
+            amount_in,
+            base_reserve as u128 + amount_in as u128,
+            (quote_reserve - amount_out) as u128,
+        )
     } else {
-        (quote_reserve, base_reserve)
-    };
+        let amount_in = buy_base_output(amount_out, base_reserve, quote_reserve, fees)?;
 
-    let input_reserve_f64 = input_reserve as f64;
-    let output_reserve_f64 = output_reserve as f64;
-    let amount_out_f64 = amount_out as f64;
-
-    let amount_in: f64;
-    let new_input_reserve: f64;
-    let new_output_reserve: f64;
+        (
+            amount_in,
+            (base_reserve - amount_out) as u128,
+            quote_reserve as u128 + amount_in as u128,
+        )
+    };
 
-    if swap_for_quote {
-        let mut total_fee_rate: u64 = 0;
-        for fee in fee_multiplier {
-            total_fee_rate += fee;
-        }
+    Ok(AmountInResult {
+        amount_in,
+        new_price: new_quote_reserve as f64 / new_base_reserve as f64,
+        new_reserves: (new_base_reserve, new_quote_reserve),
+    })
+}
 
-        let fee_numerator = amount_out_f64 * 10000.0;
-        let fee_denominator = 10000.0 - (total_fee_rate as f64);
-        let reversed_amount_out =
-            ((fee_numerator + fee_denominator) / fee_denominator).ceil() + 2.0;
+#[cfg(test)]
+mod tests_pumpswap_fixtures {
+    use super::*;
 
-        let numerator = input_reserve_f64 * reversed_amount_out;
-        let denominator = output_reserve_f64 - reversed_amount_out;
-        let raw_amount_in = (numerator / denominator).ceil();
+    struct Fixture {
+        instruction: &'static str,
+        base_reserve: u64,
+        quote_reserve: u64,
+        fees: Fees,
+        base_amount: u64,
+        quote_amount: u64,
+    }
 
-        amount_in = raw_amount_in;
+    fn fixtures() -> Vec<Fixture> {
+        include_str!("test_data/swap_fixtures.csv")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let fields = line.split(',').collect::<Vec<_>>();
+                let number = |index: usize| fields[index].parse::<u64>().unwrap();
+
+                Fixture {
+                    instruction: fields[0],
+                    base_reserve: number(1),
+                    quote_reserve: number(2),
+                    fees: Fees {
+                        lp_fee_bps: number(3),
+                        protocol_fee_bps: number(4),
+                        creator_fee_bps: number(5),
+                    },
+                    base_amount: number(6),
+                    quote_amount: number(7),
+                }
+            })
+            .collect()
+    }
 
-        new_input_reserve = input_reserve_f64 + amount_in;
-        new_output_reserve = output_reserve_f64 - amount_out_f64;
-    } else {
-        let numerator = input_reserve_f64 * amount_out_f64;
-        let denominator = output_reserve_f64 - amount_out_f64;
-        let net_amount_in = (numerator / denominator).ceil();
-
-        let mut total_fee = 0f64;
-        for fee in fee_multiplier {
-            let fee_amount = (net_amount_in * (*fee as f64) / 10000.0).ceil();
-            total_fee += fee_amount;
+    // регрессия по формульным векторам, не сверка с mainnet
+    #[test]
+    fn test_fixture_vectors() {
+        let fixtures = fixtures();
+        assert!(!fixtures.is_empty());
+
+        for f in fixtures {
+            if f.instruction == "buy_exact_quote_in" {
+                let base_out =
+                    buy_exact_quote_in(f.quote_amount, f.base_reserve, f.quote_reserve, &f.fees)
+                        .unwrap();
+                assert_eq!(base_out, f.base_amount);
+            } else if f.instruction == "buy" {
+                let quote_in =
+                    buy_base_output(f.base_amount, f.base_reserve, f.quote_reserve, &f.fees)
+                        .unwrap();
+                assert_eq!(quote_in, f.quote_amount);
+
+                let result = get_amount_in(
+                    f.base_reserve,
+                    f.quote_reserve,
+                    f.base_amount,
+                    &f.fees,
+                    false,
+                )
+                .unwrap();
+                assert_eq!(result.amount_in, f.quote_amount);
+            } else {
+                let quote_out =
+                    sell_base_input(f.base_amount, f.base_reserve, f.quote_reserve, &f.fees)
+                        .unwrap();
+                assert_eq!(quote_out, f.quote_amount);
+
+                let result = get_amount_out(
+                    f.base_reserve,
+                    f.quote_reserve,
+                    f.base_amount,
+                    &f.fees,
+                    true,
+                )
+                .unwrap();
+                assert_eq!(result.amount_out, f.quote_amount);
+                assert_eq!(result.consumed_in_amount, f.base_amount);
+            }
         }
-
-        amount_in = (net_amount_in + total_fee).ceil();
-        new_input_reserve = input_reserve_f64 + amount_in;
-        new_output_reserve = output_reserve_f64 - amount_out_f64;
     }
 
-    let new_price = new_output_reserve / new_input_reserve;
-
-    AmountInResult {
-        amount_in: amount_in as u64,
-        new_price: if swap_for_quote {
-            new_price
-        } else {
-            1.0 / new_price
-        },
+    #[test]
+    fn test_fixtures_inverse() {
+        for f in fixtures() {
+            if f.instruction == "buy_exact_quote_in" {
+                // buy с точным base на выходе за тот же quote даёт не больше base
+                let (base_out, cost) =
+                    buy_quote_input(f.quote_amount, f.base_reserve, f.quote_reserve, &f.fees)
+                        .unwrap();
+                assert!(base_out <= f.base_amount);
+                assert!(cost <= f.quote_amount);
+            } else if f.instruction == "buy" {
+                // за списанный quote покупается не меньше base, чем в транзакции
+                let (base_out, cost) =
+                    buy_quote_input(f.quote_amount, f.base_reserve, f.quote_reserve, &f.fees)
+                        .unwrap();
+                assert!(base_out >= f.base_amount);
+                assert!(cost <= f.quote_amount);
+            } else {
+                // для полученного quote хватает не большего base, чем в транзакции
+                let base_in =
+                    sell_quote_output(f.quote_amount, f.base_reserve, f.quote_reserve, &f.fees)
+                        .unwrap();
+                assert!(base_in <= f.base_amount);
+                assert!(
+                    sell_base_input(base_in, f.base_reserve, f.quote_reserve, &f.fees).unwrap()
+                        >= f.quote_amount
+                );
+            }
+        }
     }
 }
 
 #[cfg(test)]
-mod tests_pumpswap {
+mod tests_pumpswap_rounding {
     use super::*;
 
-    const SOL_RESERVE_1: u64 = 942150070694;
-    const TOKEN_RESERVE_1: u64 = 35722696881401;
-    const FEE_MULTIPLIER: &[u64] = &[20, 5, 5];
+    const BASE_RESERVE: u64 = 35722696881401;
+    const QUOTE_RESERVE: u64 = 942150070694;
+    const FEES: Fees = Fees {
+        lp_fee_bps: 20,
+        protocol_fee_bps: 5,
+        creator_fee_bps: 5,
+    };
+    const AMOUNTS: &[u64] = &[1, 10, 1_000, 123_456_789, 677_970_243, 1_000_000_000];
 
-    // ESTIMATE (FLOOR)
     #[test]
-    fn test_get_amount_out_quote_for_base() {
-        let result = get_amount_out(
-            TOKEN_RESERVE_1,
-            SOL_RESERVE_1,
-            677970243,
-            FEE_MULTIPLIER,
-            false,
-        );
-
-        assert!(result.amount_out < 25610754894);
-        assert!(result.new_price < 1.0);
+    fn test_buy_quote_input_is_max() {
+        for &quote_in in AMOUNTS {
+            let (base_out, cost) =
+                buy_quote_input(quote_in, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap();
+
+            assert!(cost <= quote_in);
+            assert_eq!(
+                cost,
+                buy_base_output(base_out, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap()
+            );
+            // на единицу base больше - уже не хватает quote
+            assert!(
+                buy_base_output(base_out + 1, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap()
+                    > quote_in
+            );
+        }
     }
 
-    // WORK PERFECTLY
     #[test]
-    fn test_get_amount_in_quote_for_base() {
-        let result = get_amount_in(
-            TOKEN_RESERVE_1,
-            SOL_RESERVE_1,
-            25610754894,
-            FEE_MULTIPLIER,
-            false,
-        );
-
-        assert_eq!(result.amount_in, 677970243);
-        assert!(result.new_price < 1.0);
+    fn test_sell_quote_output_is_min() {
+        for &quote_out in AMOUNTS {
+            let base_in = sell_quote_output(quote_out, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap();
+
+            assert!(
+                sell_base_input(base_in, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap() >= quote_out
+            );
+            assert!(
+                sell_base_input(base_in - 1, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap()
+                    < quote_out
+            );
+        }
     }
 
-    const SOL_RESERVE_2: u64 = 944608044265;
-    const TOKEN_RESERVE_2: u64 = 35683125915273;
-
-    // WORK PERFECTLY
     #[test]
-    fn test_get_amount_out_base_for_quote() {
-        let result = get_amount_out(
-            TOKEN_RESERVE_2,
-            SOL_RESERVE_2,
-            567208523585,
-            FEE_MULTIPLIER,
-            true,
+    fn test_buy_exact_quote_in() {
+        let base_out = buy_exact_quote_in(677_970_243, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap();
+        assert_eq!(base_out, 25_610_754_969);
+
+        // тот же base через buy стоит на лампорт больше: отсюда бывший +1 в pumpswap_swap
+        assert_eq!(
+            buy_base_output(base_out, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap(),
+            677_970_244
         );
-
-        assert_eq!(result.amount_out, 14735929285);
-        assert!(result.new_price < 1.0);
+        let (buy_base_out, _) =
+            buy_quote_input(677_970_243, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap();
+        assert!(buy_base_out < base_out);
     }
 
-    // ESTIMATE (FLOOR)
     #[test]
-    fn test_get_amount_in_base_for_quote() {
-        let result = get_amount_in(
-            TOKEN_RESERVE_2,
-            SOL_RESERVE_2,
-            14735929285,
-            FEE_MULTIPLIER,
-            true,
-        );
+    fn test_per_component_ceil() {
+        // 1 лампорт комиссии на каждую из трёх компонент
+        assert_eq!(total_fee(100, &FEES), 3);
+        assert_eq!(fee(10_000, 20), 20);
+        assert_eq!(fee(10_001, 20), 21);
+        assert_eq!(fee(1, 0), 0);
+    }
 
-        assert!(result.amount_in > 567208523585);
-        assert!(result.new_price < 1.0);
+    #[test]
+    fn test_no_creator_fee() {
+        let no_creator = Fees {
+            creator_fee_bps: 0,
+            ..FEES
+        };
+
+        let with_creator =
+            sell_base_input(567_208_523_585, BASE_RESERVE, QUOTE_RESERVE, &FEES).unwrap();
+        let without_creator =
+            sell_base_input(567_208_523_585, BASE_RESERVE, QUOTE_RESERVE, &no_creator).unwrap();
+        assert!(without_creator > with_creator);
     }
 }
 
 #[cfg(test)]
-mod tests_pumpswap_real {
+mod tests_edge_case_reserves {
     use super::*;
 
-    const FEE_MULTIPLIER: &[u64] = &[20, 5, 5];
+    const FEES: Fees = Fees {
+        lp_fee_bps: 20,
+        protocol_fee_bps: 5,
+        creator_fee_bps: 5,
+    };
 
     #[test]
-    fn test_get_amount_out_quote_for_base() {
-        let meteora_to_ps = get_amount_out(
-            11543520807844,
-            2250653386181,
-            7219526759,
-            FEE_MULTIPLIER,
-            true,
-        );
+    fn test_fee_eats_output() {
+        let result = get_amount_out(37196048340837, 753145324240, 1, &FEES, true).unwrap();
 
-        let ps_to_meteora = get_amount_in(
-            29886053975948,
-            5701860153537,
-            10490409765,
-            FEE_MULTIPLIER,
-            false,
-        );
+        // комиссия съедает весь выход
+        assert_eq!(result.amount_out, 0);
+        assert!(result.new_price < 1.0);
+    }
 
-        assert_eq!(meteora_to_ps.amount_out, 1402499398);
-        assert_eq!(ps_to_meteora.amount_in, 2008139324);
+    #[test]
+    fn test_zero_reserve() {
+        assert_eq!(
+            get_amount_out(0, 1_000_000, 1000, &FEES, true).unwrap_err(),
+            ErrorCode::ZeroReserve.into()
+        );
+        assert_eq!(
+            get_amount_out(1_000_000, 0, 1000, &FEES, false).unwrap_err(),
+            ErrorCode::ZeroReserve.into()
+        );
+        assert_eq!(
+            get_amount_in(0, 1_000_000, 1000, &FEES, false).unwrap_err(),
+            ErrorCode::ZeroReserve.into()
+        );
     }
-}
 
-#[cfg(test)]
-mod tests_pumpswap_4 {
-    use super::*;
+    #[test]
+    fn test_drained_pool() {
+        assert_eq!(
+            get_amount_in(1_000_000, 1_000_000, 1_000_000, &FEES, false).unwrap_err(),
+            ErrorCode::OutputExceedsReserve.into()
+        );
+        // с учётом комиссии выход до fee превышает резерв
+        assert_eq!(
+            get_amount_in(1_000_000, 1_000_000, 999_000, &FEES, true).unwrap_err(),
+            ErrorCode::OutputExceedsReserve.into()
+        );
 
-    const SOL_RESERVE: u64 = 753145324240;
-    const TOKEN_RESERVE: u64 = 37196048340837;
-    const FEE_MULTIPLIER: &[u64] = &[20, 5, 5];
+        let result = get_amount_out(1_000_000, 1, 1_000_000_000, &FEES, true).unwrap();
+        assert_eq!(result.amount_out, 0);
+    }
 
     #[test]
-    fn test_get_amount_out_base_for_quote() {
-        let result = get_amount_out(TOKEN_RESERVE, SOL_RESERVE, 1, FEE_MULTIPLIER, true);
+    fn test_max_reserves() {
+        let result = get_amount_out(u64::MAX, u64::MAX, 1_000_000_000, &FEES, true).unwrap();
+        assert!(result.amount_out > 0 && result.amount_out < 1_000_000_000);
 
-        assert_eq!(result.amount_out, 1);
-        assert!(result.new_price < 1.0);
+        let result = get_amount_out(u64::MAX, u64::MAX, 1_000_000_000, &FEES, false).unwrap();
+        assert!(result.amount_out > 0 && result.amount_out < 1_000_000_000);
+        assert!(result.consumed_in_amount <= 1_000_000_000);
+
+        assert_eq!(
+            get_amount_in(1_000_000, u64::MAX, 999_999, &FEES, false).unwrap_err(),
+            ErrorCode::Overflow.into()
+        );
     }
 }
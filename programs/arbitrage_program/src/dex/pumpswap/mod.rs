@@ -9,3 +9,6 @@ pub use math::*;
 
 pub mod fee_math;
 pub use fee_math::*;
+
+pub mod pdas;
+pub use pdas::*;
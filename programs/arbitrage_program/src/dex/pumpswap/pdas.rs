@@ -0,0 +1,283 @@
+use crate::dex::{PUMPSWAP_FEE_PROGRAM_ID, PUMPSWAP_PROGRAM_ID};
+use crate::error::ErrorCode;
+use crate::state::{PumpswapAccounts, PumpswapSlimAccounts};
+use crate::Result;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+pub const CREATOR_VAULT_SEED: &[u8] = b"creator_vault";
+pub const GLOBAL_VOLUME_ACCUMULATOR_SEED: &[u8] = b"global_volume_accumulator";
+pub const USER_VOLUME_ACCUMULATOR_SEED: &[u8] = b"user_volume_accumulator";
+pub const FEE_CONFIG_SEED: &[u8] = b"fee_config";
+
+// PDA, не зависящие от пула, для mainnet program id: сравнение вместо find_program_address
+pub const PUMPSWAP_EVENT_AUTHORITY: Pubkey =
+    pubkey!("GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR");
+pub const PUMPSWAP_GLOBAL_VOLUME_ACCUMULATOR: Pubkey =
+    pubkey!("C2aFPdENg4A2HQsmrd5rTw5TaYBX5Ku887cWjbFKtZpw");
+pub const PUMPSWAP_FEE_CONFIG: Pubkey = pubkey!("5PHirr8joyTMp9JMm6nW7hNDVyEYdkzDqazxPD7RaTjx");
+
+/// Вспомогательные аккаунты buy/sell, которые вычисляются из пула и пользователя.
+/// find_program_address стоит ~1500 CU за каждую попытку bump (обычно 1-2 попытки):
+/// - event_authority, global_volume_accumulator, fee_config - 0 CU для mainnet program id, иначе по одному find
+/// - creator_vault - два find: authority от coin_creator и её ATA в quote минте
+/// - user_volume_accumulator - один find от подписанта свапа
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PumpswapDerivedAccounts {
+    pub event_authority: Pubkey,
+    pub creator_vault_authority: Pubkey,
+    pub creator_vault: Pubkey,
+    pub global_volume_accumulator: Pubkey,
+    pub user_volume_accumulator: Pubkey,
+    pub fee_config: Pubkey,
+}
+
+impl PumpswapDerivedAccounts {
+    pub fn derive(
+        program_id: &Pubkey,
+        fee_program_id: &Pubkey,
+        coin_creator: &Pubkey,
+        quote_mint: &Pubkey,
+        quote_token_program: &Pubkey,
+        user: &Pubkey,
+    ) -> Self {
+        let is_mainnet = *program_id == PUMPSWAP_PROGRAM_ID;
+        let find =
+            |seeds: &[&[u8]], program: &Pubkey| Pubkey::find_program_address(seeds, program).0;
+
+        let (event_authority, global_volume_accumulator) = if is_mainnet {
+            (PUMPSWAP_EVENT_AUTHORITY, PUMPSWAP_GLOBAL_VOLUME_ACCUMULATOR)
+        } else {
+            (
+                find(&[EVENT_AUTHORITY_SEED], program_id),
+                find(&[GLOBAL_VOLUME_ACCUMULATOR_SEED], program_id),
+            )
+        };
+
+        let fee_config = if is_mainnet && *fee_program_id == PUMPSWAP_FEE_PROGRAM_ID {
+            PUMPSWAP_FEE_CONFIG
+        } else {
+            find(&[FEE_CONFIG_SEED, program_id.as_ref()], fee_program_id)
+        };
+
+        let creator_vault_authority =
+            find(&[CREATOR_VAULT_SEED, coin_creator.as_ref()], program_id);
+        let creator_vault = get_associated_token_address_with_program_id(
+            &creator_vault_authority,
+            quote_mint,
+            quote_token_program,
+        );
+
+        Self {
+            event_authority,
+            creator_vault_authority,
+            creator_vault,
+            global_volume_accumulator,
+            user_volume_accumulator: find(
+                &[USER_VOLUME_ACCUMULATOR_SEED, user.as_ref()],
+                program_id,
+            ),
+            fee_config,
+        }
+    }
+
+    /// Сверяет переданные аккаунты с вычисленными
+    pub fn check(&self, accounts: &PumpswapAccounts) -> Result<()> {
+        require_keys_eq!(
+            accounts.creator_vault.key(),
+            self.creator_vault,
+            ErrorCode::PumpswapCreatorVaultMismatch
+        );
+        require_keys_eq!(
+            accounts.creator.key(),
+            self.creator_vault_authority,
+            ErrorCode::PumpswapCreatorVaultMismatch
+        );
+
+        let derived = [
+            (accounts.event_authority.key(), self.event_authority),
+            (
+                accounts.global_volume_accumulator.key(),
+                self.global_volume_accumulator,
+            ),
+            (
+                accounts.user_volume_accumulator.key(),
+                self.user_volume_accumulator,
+            ),
+            (accounts.fee_config.key(), self.fee_config),
+        ];
+        for (passed, expected) in derived {
+            require_keys_eq!(passed, expected, ErrorCode::PumpswapDerivedAccountMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Дополняет ногу PumpswapSlimAccounts до полного PumpswapAccounts:
+    /// выведенные аккаунты ищутся по ключу среди accounts
+    pub fn fill<'info>(
+        &self,
+        slim: &PumpswapSlimAccounts<'info>,
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<PumpswapAccounts<'info>> {
+        let find = |key: &Pubkey| {
+            accounts
+                .iter()
+                .find(|account| account.key == key)
+                .cloned()
+                .ok_or_else(|| error!(ErrorCode::PumpswapDerivedAccountMissing))
+        };
+
+        Ok(PumpswapAccounts {
+            program_id: slim.program_id.clone(),
+            pool: slim.pool.clone(),
+            global_config: slim.global_config.clone(),
+            base_mint: slim.base_mint.clone(),
+            quote_mint: slim.quote_mint.clone(),
+            base_token_account: slim.base_token_account.clone(),
+            quote_token_account: slim.quote_token_account.clone(),
+            protocol_fee_recipient: slim.protocol_fee_recipient.clone(),
+            protocol_fee_recipient_token_account: slim.protocol_fee_recipient_token_account.clone(),
+            event_authority: find(&self.event_authority)?,
+            creator_vault: find(&self.creator_vault)?,
+            creator: find(&self.creator_vault_authority)?,
+            global_volume_accumulator: find(&self.global_volume_accumulator)?,
+            user_volume_accumulator: find(&self.user_volume_accumulator)?,
+            fee_config: find(&self.fee_config)?,
+            fee_program: slim.fee_program.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests_pumpswap_pdas {
+    use super::*;
+    use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+
+    #[test]
+    fn test_mainnet_constants() {
+        let find =
+            |seeds: &[&[u8]], program: &Pubkey| Pubkey::find_program_address(seeds, program).0;
+
+        assert_eq!(
+            find(&[EVENT_AUTHORITY_SEED], &PUMPSWAP_PROGRAM_ID),
+            PUMPSWAP_EVENT_AUTHORITY
+        );
+        assert_eq!(
+            find(&[GLOBAL_VOLUME_ACCUMULATOR_SEED], &PUMPSWAP_PROGRAM_ID),
+            PUMPSWAP_GLOBAL_VOLUME_ACCUMULATOR
+        );
+        assert_eq!(
+            find(
+                &[FEE_CONFIG_SEED, PUMPSWAP_PROGRAM_ID.as_ref()],
+                &PUMPSWAP_FEE_PROGRAM_ID
+            ),
+            PUMPSWAP_FEE_CONFIG
+        );
+    }
+
+    #[test]
+    fn test_derive_overridden_program() {
+        let program_id = Pubkey::new_unique();
+        let fee_program_id = Pubkey::new_unique();
+        let coin_creator = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        let derived = PumpswapDerivedAccounts::derive(
+            &program_id,
+            &fee_program_id,
+            &coin_creator,
+            &quote_mint,
+            &TOKEN_PROGRAM_ID,
+            &user,
+        );
+        let mainnet = PumpswapDerivedAccounts::derive(
+            &PUMPSWAP_PROGRAM_ID,
+            &PUMPSWAP_FEE_PROGRAM_ID,
+            &coin_creator,
+            &quote_mint,
+            &TOKEN_PROGRAM_ID,
+            &user,
+        );
+
+        assert_ne!(derived.event_authority, PUMPSWAP_EVENT_AUTHORITY);
+        assert_ne!(derived.fee_config, PUMPSWAP_FEE_CONFIG);
+        assert_ne!(derived.creator_vault, mainnet.creator_vault);
+        assert_eq!(
+            derived.creator_vault,
+            get_associated_token_address_with_program_id(
+                &derived.creator_vault_authority,
+                &quote_mint,
+                &TOKEN_PROGRAM_ID
+            )
+        );
+    }
+
+    #[test]
+    fn test_fill_slim_accounts() {
+        let user = Pubkey::new_unique();
+        let derived = PumpswapDerivedAccounts::derive(
+            &PUMPSWAP_PROGRAM_ID,
+            &PUMPSWAP_FEE_PROGRAM_ID,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &TOKEN_PROGRAM_ID,
+            &user,
+        );
+
+        // 10 аккаунтов slim-ноги и выведенные аккаунты в произвольном порядке с лишним в начале
+        let mut keys = (0..PumpswapSlimAccounts::LEN)
+            .map(|_| Pubkey::new_unique())
+            .collect::<Vec<_>>();
+        keys.extend([
+            Pubkey::new_unique(),
+            derived.fee_config,
+            derived.user_volume_accumulator,
+            derived.creator_vault,
+            derived.event_authority,
+            derived.global_volume_accumulator,
+            derived.creator_vault_authority,
+        ]);
+        let mut lamports = vec![0u64; keys.len()];
+        let mut data = vec![Vec::<u8>::new(); keys.len()];
+        let owner = Pubkey::default();
+        let infos = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, true, lamports, data, &owner, false, 0)
+            })
+            .collect::<Vec<_>>();
+
+        let slim = PumpswapSlimAccounts {
+            program_id: infos[0].clone(),
+            pool: infos[1].clone(),
+            global_config: infos[2].clone(),
+            base_mint: infos[3].clone(),
+            quote_mint: infos[4].clone(),
+            base_token_account: infos[5].clone(),
+            quote_token_account: infos[6].clone(),
+            protocol_fee_recipient: infos[7].clone(),
+            protocol_fee_recipient_token_account: infos[8].clone(),
+            fee_program: infos[9].clone(),
+        };
+        let tail = &infos[PumpswapSlimAccounts::LEN..];
+
+        let accounts = derived.fill(&slim, tail).unwrap();
+        assert_eq!(accounts.pool.key, slim.pool.key);
+        assert_eq!(accounts.fee_program.key, slim.fee_program.key);
+        assert!(derived.check(&accounts).is_ok());
+
+        // без creator_vault_authority нога не собирается
+        assert_eq!(
+            derived
+                .fill(&slim, &tail[..tail.len() - 1])
+                .map(|_| ())
+                .unwrap_err(),
+            ErrorCode::PumpswapDerivedAccountMissing.into()
+        );
+    }
+}
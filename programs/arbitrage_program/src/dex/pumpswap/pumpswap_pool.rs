@@ -1,19 +1,32 @@
-use crate::arbitrage_engine::{BasePool, LiquidityType, SwapResult};
-use crate::commons::get_ata_balance;
+use crate::arbitrage_engine::{BasePool, LiquidityType, PoolKind, SwapResult};
+use crate::commons::{
+    amount_after_transfer_fee, amount_before_transfer_fee, get_ata_balance, get_transfer_fee,
+    resolve_token_program, SwapAuthority,
+};
 use crate::dex::pumpswap::*;
 use crate::error::ErrorCode;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFee;
+use anchor_spl::token_interface::Mint;
 
 pub struct PumpswapPool<'b, 'info> {
     pub is_source_quote: bool,
     pub protocol_fee_basis_points: u64,
     pub lp_fee_basis_points: u64,
     pub coin_creator_fee_basis_points: u64,
+    pub fee_schedule: FeeSchedule,
+    pub disable_flags: u8,
     pub base_reserve: u64,
     pub quote_reserve: u64,
 
+    // Токен-программы и transfer-fee Token-2022 для base и quote минтов
+    pub base_token_program: AccountInfo<'info>,
+    pub quote_token_program: AccountInfo<'info>,
+    pub base_transfer_fee: Option<TransferFee>,
+    pub quote_transfer_fee: Option<TransferFee>,
+
+    pub authority: SwapAuthority<'b, 'info>,
     pub accounts: &'b PumpswapAccounts<'info>,
     pub common: &'b CommonAccounts<'info>,
 }
@@ -22,13 +35,42 @@ impl<'b, 'info> PumpswapPool<'b, 'info> {
     pub fn new(
         common: &'b CommonAccounts<'info>,
         accounts: &'b PumpswapAccounts<'info>,
+        authority: SwapAuthority<'b, 'info>,
+        verify_pdas: bool,
     ) -> Result<Self> {
         // Deserialization 232 CU
         let pool_bytes = accounts.pool.try_borrow_data()?;
         let (base_mint_pubkey, quote_mint_pubkey) = PfAmmPool::deserialize_mints(&pool_bytes)?;
+        let (pool_base_token_account, pool_quote_token_account) =
+            PfAmmPool::deserialize_token_accounts(&pool_bytes)?;
+        let coin_creator = PfAmmPool::deserialize_coin_creator(&pool_bytes)?;
+        let pool_creator = PfAmmPool::deserialize_creator(&pool_bytes)?;
+
+        // Минты и vault-ы должны совпадать с записанными в пуле
+        require_keys_eq!(
+            accounts.base_mint.key(),
+            base_mint_pubkey,
+            ErrorCode::PumpswapBaseMintMismatch
+        );
+        require_keys_eq!(
+            accounts.quote_mint.key(),
+            quote_mint_pubkey,
+            ErrorCode::PumpswapQuoteMintMismatch
+        );
+        require_keys_eq!(
+            accounts.base_token_account.key(),
+            pool_base_token_account,
+            ErrorCode::PumpswapBaseVaultMismatch
+        );
+        require_keys_eq!(
+            accounts.quote_token_account.key(),
+            pool_quote_token_account,
+            ErrorCode::PumpswapQuoteVaultMismatch
+        );
 
-        // let global_config_bytes = accounts.global_config.try_borrow_data()?;
-        // let global_config = GlobalConfig::deserialize_data(&global_config_bytes)?;
+        // Из GlobalConfig нужны только disable_flags
+        let global_config_bytes = accounts.global_config.try_borrow_data()?;
+        let disable_flags = GlobalConfig::deserialize_disable_flags(&global_config_bytes)?;
 
         let fee_config_bytes = accounts.fee_config.try_borrow_data()?;
         let fee_config = FeeConfig::deserialize_data(&fee_config_bytes)?;
@@ -61,8 +103,36 @@ impl<'b, 'info> PumpswapPool<'b, 'info> {
         let base_mint_info = Mint::try_deserialize(&mut accounts.base_mint.data.borrow().as_ref())?;
         let base_mint_supply = base_mint_info.supply;
 
-        // Рассчитываем комиссии с использованием новой логики
-        let fees = compute_fees_bps(fee_config, base_mint_supply, base_reserve, quote_reserve)?;
+        // Тиры по market cap действуют только для пулов pump.fun, остальные платят flat_fees
+        let fee_schedule = FeeSchedule::new(
+            fee_config,
+            || is_pump_pool(&base_mint_pubkey, &pool_creator),
+            base_mint_supply,
+        );
+        let mut fees = fee_schedule.fees(base_reserve, quote_reserve)?;
+
+        // Pumpswap берёт creator fee только с пулов, у которых задан coin_creator
+        if coin_creator == Pubkey::default() {
+            fees.creator_fee_bps = 0;
+        }
+
+        let base_token_program = resolve_token_program(&accounts.base_mint, common)?;
+        let quote_token_program = resolve_token_program(&accounts.quote_mint, common)?;
+
+        // Без проверки PDA передаются как есть, и ошибку вернёт уже сам Pumpswap при CPI
+        if verify_pdas {
+            PumpswapDerivedAccounts::derive(
+                accounts.program_id.key,
+                accounts.fee_program.key,
+                &coin_creator,
+                &quote_mint_pubkey,
+                quote_token_program.key,
+                &authority.key(),
+            )
+            .check(accounts)?;
+        }
+        let base_transfer_fee = get_transfer_fee(&accounts.base_mint)?;
+        let quote_transfer_fee = get_transfer_fee(&accounts.quote_mint)?;
 
         // msg!(
         //     "base_reserve={}, quote_reserve={}, fees: lp={}, protocol={}, creator={}",
@@ -78,14 +148,30 @@ impl<'b, 'info> PumpswapPool<'b, 'info> {
             protocol_fee_basis_points: fees.protocol_fee_bps,
             lp_fee_basis_points: fees.lp_fee_bps,
             coin_creator_fee_basis_points: fees.creator_fee_bps,
+            fee_schedule,
+            disable_flags,
             base_reserve,
             quote_reserve,
 
+            base_token_program,
+            quote_token_program,
+            base_transfer_fee,
+            quote_transfer_fee,
+
+            authority,
             accounts,
             common,
         })
     }
 
+    pub fn fees(&self) -> Fees {
+        Fees {
+            lp_fee_bps: self.lp_fee_basis_points,
+            protocol_fee_bps: self.protocol_fee_basis_points,
+            creator_fee_bps: self.coin_creator_fee_basis_points,
+        }
+    }
+
     // Вспомогательный метод для нормализации направления свопа
     pub fn normalize_swap_direction(&self, swap_for_quote: bool) -> bool {
         if self.is_source_quote {
@@ -102,43 +188,102 @@ impl<'b, 'info> PumpswapPool<'b, 'info> {
             1.0 / price
         }
     }
+
+    // Transfer-fee входящего и исходящего токена для нормализованного направления
+    pub fn transfer_fees(
+        &self,
+        normalized_swap_for_quote: bool,
+    ) -> (Option<&TransferFee>, Option<&TransferFee>) {
+        if normalized_swap_for_quote {
+            (
+                self.base_transfer_fee.as_ref(),
+                self.quote_transfer_fee.as_ref(),
+            )
+        } else {
+            (
+                self.quote_transfer_fee.as_ref(),
+                self.base_transfer_fee.as_ref(),
+            )
+        }
+    }
+
+    // Комиссия считается по тиру до сделки, но цена после неё может перевести пул в другой тир
+    pub fn crosses_fee_tier(&self, new_reserves: (u128, u128)) -> Result<bool> {
+        self.fee_schedule
+            .crosses_tier(self.base_reserve, self.quote_reserve, new_reserves)
+    }
+
+    // Проверка disable_flags из GlobalConfig: swap_for_quote = sell, иначе buy
+    pub fn check_trade_enabled(&self, normalized_swap_for_quote: bool) -> Result<()> {
+        check_trade_enabled(self.disable_flags, normalized_swap_for_quote)
+    }
+}
+
+pub fn check_trade_enabled(disable_flags: u8, swap_for_quote: bool) -> Result<()> {
+    if swap_for_quote {
+        require!(
+            disable_flags & DISABLE_SELL_FLAG == 0,
+            ErrorCode::PumpswapSellDisabled
+        );
+    } else {
+        require!(
+            disable_flags & DISABLE_BUY_FLAG == 0,
+            ErrorCode::PumpswapBuyDisabled
+        );
+    }
+
+    Ok(())
 }
 
 impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
     fn get_fee_rate_f64(&self) -> f64 {
-        let fee_denominator = 10000;
+        let fee_denominator = 10000u64;
         let fee_multiplier = fee_denominator
-            - self.protocol_fee_basis_points
-            - self.lp_fee_basis_points
-            - self.coin_creator_fee_basis_points;
+            .saturating_sub(self.protocol_fee_basis_points)
+            .saturating_sub(self.lp_fee_basis_points)
+            .saturating_sub(self.coin_creator_fee_basis_points);
 
         1.0 - ((fee_multiplier as f64) / (fee_denominator as f64))
     }
 
     fn get_price(&self) -> Result<f64> {
+        require!(
+            self.base_reserve != 0 && self.quote_reserve != 0,
+            ErrorCode::ZeroReserve
+        );
+
         Ok(self.normalize_price(self.quote_reserve as f64 / self.base_reserve as f64))
     }
 
     fn get_amount_out(&self, amount_in: u64, swap_for_quote: bool) -> Result<SwapResult> {
         let normalized_swap_for_quote = self.normalize_swap_direction(swap_for_quote);
+        self.check_trade_enabled(normalized_swap_for_quote)?;
+
+        // Пул получает вход за вычетом transfer-fee, пользователь - выход за вычетом transfer-fee
+        let (fee_in, fee_out) = self.transfer_fees(normalized_swap_for_quote);
+        let pool_amount_in = amount_after_transfer_fee(fee_in, amount_in)?;
 
         let result = get_amount_out(
             self.base_reserve,
             self.quote_reserve,
-            amount_in,
-            &[
-                self.coin_creator_fee_basis_points,
-                self.lp_fee_basis_points,
-                self.protocol_fee_basis_points,
-            ],
+            pool_amount_in,
+            &self.fees(),
             normalized_swap_for_quote,
-        );
+        )?;
+
+        // При покупке за quote может списаться меньше amount_in: base на выходе дискретен
+        let consumed_in_amount = if result.consumed_in_amount < pool_amount_in {
+            amount_before_transfer_fee(fee_in, result.consumed_in_amount)?.min(amount_in)
+        } else {
+            amount_in
+        };
 
         let res = SwapResult {
-            amount_out: result.amount_out,
-            consumed_in_amount: amount_in,
+            amount_out: amount_after_transfer_fee(fee_out, result.amount_out)?,
+            consumed_in_amount,
             is_fully_filled: true,
             new_price: self.normalize_price(result.new_price),
+            crosses_fee_tier: self.crosses_fee_tier(result.new_reserves)?,
         };
 
         Ok(res)
@@ -146,24 +291,25 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
 
     fn get_amount_in(&self, amount_out: u64, swap_for_quote: bool) -> Result<SwapResult> {
         let normalized_swap_for_quote = self.normalize_swap_direction(swap_for_quote);
+        self.check_trade_enabled(normalized_swap_for_quote)?;
+
+        let (fee_in, fee_out) = self.transfer_fees(normalized_swap_for_quote);
+        let pool_amount_out = amount_before_transfer_fee(fee_out, amount_out)?;
 
         let result = get_amount_in(
             self.base_reserve,
             self.quote_reserve,
-            amount_out,
-            &[
-                self.coin_creator_fee_basis_points,
-                self.lp_fee_basis_points,
-                self.protocol_fee_basis_points,
-            ],
+            pool_amount_out,
+            &self.fees(),
             normalized_swap_for_quote,
-        );
+        )?;
 
         Ok(SwapResult {
             amount_out,
-            consumed_in_amount: result.amount_in,
+            consumed_in_amount: amount_before_transfer_fee(fee_in, result.amount_in)?,
             is_fully_filled: true,
             new_price: self.normalize_price(result.new_price),
+            crosses_fee_tier: self.crosses_fee_tier(result.new_reserves)?,
         })
     }
 
@@ -171,10 +317,22 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
         Ok(LiquidityType::Constant)
     }
 
+    fn get_pool_kind(&self) -> PoolKind {
+        PoolKind::Pumpswap
+    }
+
+    fn get_pool_key(&self) -> Pubkey {
+        self.accounts.pool.key()
+    }
+
     fn get_base_quote_product(&self) -> Result<u128> {
         Ok(self.base_reserve as u128 * self.quote_reserve as u128)
     }
 
+    fn get_reserves(&self) -> Result<(u64, u64)> {
+        Ok((self.base_reserve, self.quote_reserve))
+    }
+
     fn get_max_amount(&self, _price_delta: f64, _base_to_quote: bool) -> Result<u64> {
         Ok(u64::MAX)
     }
@@ -197,14 +355,20 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
             )
         };
 
-        if (source_to_intermediate && self.is_source_quote)
-            || (!source_to_intermediate && !self.is_source_quote)
-        {
+        let is_buy = (source_to_intermediate && self.is_source_quote)
+            || (!source_to_intermediate && !self.is_source_quote);
+        self.check_trade_enabled(!is_buy)?;
+
+        // min_amount_out задан в полученных пользователем токенах, DEX проверяет сумму до transfer-fee
+        let (_, fee_out) = self.transfer_fees(!is_buy);
+        let min_amount_out = amount_before_transfer_fee(fee_out, min_amount_out)?;
+
+        if is_buy {
             // Вызываем buy
             pumpswap_buy(
                 &self.accounts.program_id,
                 &self.accounts.pool,
-                &self.common.user,
+                &self.authority,
                 &self.accounts.global_config,
                 &self.accounts.base_mint,
                 &self.accounts.quote_mint,
@@ -214,8 +378,8 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
                 &self.accounts.quote_token_account,
                 &self.accounts.protocol_fee_recipient,
                 &self.accounts.protocol_fee_recipient_token_account,
-                &self.common.token_program,
-                &self.common.token_program,
+                &self.base_token_program,
+                &self.quote_token_program,
                 &self.common.system_program,
                 &self.common.associated_token_program,
                 &self.accounts.event_authority,
@@ -233,7 +397,7 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
             pumpswap_sell(
                 &self.accounts.program_id,
                 &self.accounts.pool,
-                &self.common.user,
+                &self.authority,
                 &self.accounts.global_config,
                 &self.accounts.base_mint,
                 &self.accounts.quote_mint,
@@ -243,8 +407,8 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
                 &self.accounts.quote_token_account,
                 &self.accounts.protocol_fee_recipient,
                 &self.accounts.protocol_fee_recipient_token_account,
-                &self.common.token_program,
-                &self.common.token_program,
+                &self.base_token_program,
+                &self.quote_token_program,
                 &self.common.system_program,
                 &self.common.associated_token_program,
                 &self.accounts.event_authority,
@@ -257,4 +421,41 @@ impl<'b, 'info> BasePool for PumpswapPool<'b, 'info> {
             )
         }
     }
+
+    // Покупка задаётся выходом base: берём ровно intermediate_amount, а max_quote_amount_in -
+    // рассчитанная стоимость amount_in из поиска, так что buy не спишет больше расчётного.
+    // Если расчёт разойдётся с pAMM, нога упадёт на проверке слиппеджа, а не переплатит
+    fn swap_first_leg(&self, amount_in: u64, intermediate_amount: u64) -> Result<()> {
+        if self.is_source_quote {
+            self.swap(amount_in, intermediate_amount, true)
+        } else {
+            self.swap(amount_in, 0, true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_check_trade_enabled {
+    use super::*;
+
+    #[test]
+    fn test_disable_flags() {
+        assert!(check_trade_enabled(0, true).is_ok());
+        assert!(check_trade_enabled(0, false).is_ok());
+
+        assert_eq!(
+            check_trade_enabled(DISABLE_BUY_FLAG, false).unwrap_err(),
+            ErrorCode::PumpswapBuyDisabled.into()
+        );
+        assert!(check_trade_enabled(DISABLE_BUY_FLAG, true).is_ok());
+
+        assert_eq!(
+            check_trade_enabled(DISABLE_SELL_FLAG, true).unwrap_err(),
+            ErrorCode::PumpswapSellDisabled.into()
+        );
+        assert!(check_trade_enabled(DISABLE_SELL_FLAG, false).is_ok());
+
+        // остальные биты (create pool / deposit / withdraw) на свапы не влияют
+        assert!(check_trade_enabled(0b111, true).is_ok());
+    }
 }
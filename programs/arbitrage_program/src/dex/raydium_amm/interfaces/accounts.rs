@@ -107,6 +107,9 @@ pub struct AmmInfo {
     pub padding2: u64,
 }
 
+// Размер аккаунта AmmInfo, у Raydium AMM v4 нет дискриминатора
+pub const AMM_INFO_SIZE: usize = 752;
+
 impl AmmInfo {
     pub fn deserialize_data(data: &[u8]) -> Result<AmmInfo> {
         let result: AmmInfo =
@@ -116,9 +119,8 @@ impl AmmInfo {
     }
 
     pub fn deserialize_mints(data: &[u8]) -> Result<(Pubkey, Pubkey)> {
-        // Проверка, чтобы хватило данных до минтов
-        let coin_vault_mint_offset = 0    // дискриминатор
-            + 8 * 16                      // 16 полей по 8 байт (u64)
+        // Проверка, чтобы хватило данных до минтов (дискриминатора у AmmInfo нет)
+        let coin_vault_mint_offset = 8 * 16 // 16 полей по 8 байт (u64)
             + 8 * 8                       // Fees: 8 полей по 8 байт
             + 8 * 10 + 4 * 16             // StateData: 10 полей по 8 байт и 4 поля по 16
             + 32 * 2; // 2 pubkey
@@ -144,14 +146,72 @@ impl AmmInfo {
         Ok((coin_vault_mint, pc_vault_mint))
     }
 
+    /// Возвращает (status, state, state_data.pool_open_time)
+    pub fn deserialize_status(data: &[u8]) -> Result<(u64, u64, u64)> {
+        let status_offset = 0;
+        let state_offset = 8 * 6; // status, nonce, order_num, depth, coin_decimals, pc_decimals
+        let pool_open_time_offset = 8 * 16    // 16 полей по 8 байт (до Fees)
+            + 8 * 8                           // Fees: 8 полей по 8 байт
+            + 8 * 4; // StateData: need_take_pnl_coin, need_take_pnl_pc, total_pnl_pc, total_pnl_coin
+
+        if data.len() < pool_open_time_offset + 8 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let status = u64::from_le_bytes(
+            data[status_offset..status_offset + 8]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+
+        let state = u64::from_le_bytes(
+            data[state_offset..state_offset + 8]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+
+        let pool_open_time = u64::from_le_bytes(
+            data[pool_open_time_offset..pool_open_time_offset + 8]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+
+        Ok((status, state, pool_open_time))
+    }
+
+    pub fn deserialize_vaults(data: &[u8]) -> Result<(Pubkey, Pubkey)> {
+        let coin_vault_offset = 8 * 16    // 16 полей по 8 байт (u64)
+            + 8 * 8                       // Fees: 8 полей по 8 байт
+            + 8 * 10 + 4 * 16; // StateData: 10 полей по 8 байт и 4 поля по 16
+
+        let pc_vault_offset = coin_vault_offset + 32;
+
+        if data.len() < pc_vault_offset + 32 {
+            return Err(ErrorCode::InvalidAccount.into());
+        }
+
+        let coin_vault = Pubkey::new_from_array(
+            data[coin_vault_offset..coin_vault_offset + 32]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+
+        let pc_vault = Pubkey::new_from_array(
+            data[pc_vault_offset..pc_vault_offset + 32]
+                .try_into()
+                .map_err(|_| ErrorCode::InvalidAccount)?,
+        );
+
+        Ok((coin_vault, pc_vault))
+    }
+
     pub fn deserialize_swap_fees(data: &[u8]) -> Result<(u64, u64)> {
         if data.len() < 8 {
             return Err(ErrorCode::InvalidAccount.into());
         }
 
         // offset к Fees
-        let swap_fee_numerator_offset = 0    // дискриминатор
-            + 8 * 16                         // 16 полей по 8 байт (до Fees)
+        let swap_fee_numerator_offset = 8 * 16 // 16 полей по 8 байт (до Fees)
             + 8 * 6; // 6 полей внутри Fees
 
         let swap_fee_denominator_offset = swap_fee_numerator_offset + 8; // сразу после numerator
@@ -181,8 +241,7 @@ impl AmmInfo {
         }
 
         // offset к StateData
-        let state_data_offset = 0    // дискриминатор
-            + 8 * 16                 // 16 полей по 8 байт (до Fees)
+        let state_data_offset = 8 * 16 // 16 полей по 8 байт (до Fees)
             + 8 * 8; // Fees: 8 полей по 8 байт
 
         let need_take_pnl_coin_offset = state_data_offset;
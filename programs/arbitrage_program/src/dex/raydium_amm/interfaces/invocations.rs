@@ -1,8 +1,9 @@
+use crate::commons::SwapAuthority;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
-use anchor_lang::solana_program::program::invoke;
 
 /// Executes a Raydium AMM V4 swap
+#[allow(clippy::too_many_arguments)]
 pub fn raydium_amm_v4_swap<'info>(
     token_program: &AccountInfo<'info>,
     amm_program: &AccountInfo<'info>,
@@ -21,7 +22,7 @@ pub fn raydium_amm_v4_swap<'info>(
     market_vault_signer: &AccountInfo<'info>,
     user_source: &AccountInfo<'info>,
     user_destination: &AccountInfo<'info>,
-    user_wallet: &Signer<'info>,
+    user_wallet: &SwapAuthority<'_, 'info>,
     amount_in: u64,
     minimum_amount_out: u64,
 ) -> Result<()> {
@@ -84,7 +85,7 @@ pub fn raydium_amm_v4_swap<'info>(
     ];
 
     // Execute the instruction
-    invoke(&ix, account_infos)?;
+    user_wallet.invoke(&ix, account_infos)?;
 
     Ok(())
 }
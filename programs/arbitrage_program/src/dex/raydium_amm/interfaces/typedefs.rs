@@ -23,3 +23,56 @@ pub struct Fees {
     /// denominator of the swap_fee
     pub swap_fee_denominator: u64,
 }
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u64)]
+pub enum AmmStatus {
+    Uninitialized = 0u64,
+    Initialized = 1u64,
+    Disabled = 2u64,
+    WithdrawOnly = 3u64,
+    // pool only can add or remove liquidity, can't swap and plan orders
+    LiquidityOnly = 4u64,
+    // pool only can add or remove liquidity and plan orders, can't swap
+    OrderBookOnly = 5u64,
+    // pool only can add or remove liquidity and swap, can't plan orders
+    SwapOnly = 6u64,
+    // pool status after created and will auto update to SwapOnly during swap after open_time
+    WaitingTrade = 7u64,
+}
+
+impl AmmStatus {
+    pub fn from_u64(status: u64) -> Option<Self> {
+        match status {
+            0 => Some(AmmStatus::Uninitialized),
+            1 => Some(AmmStatus::Initialized),
+            2 => Some(AmmStatus::Disabled),
+            3 => Some(AmmStatus::WithdrawOnly),
+            4 => Some(AmmStatus::LiquidityOnly),
+            5 => Some(AmmStatus::OrderBookOnly),
+            6 => Some(AmmStatus::SwapOnly),
+            7 => Some(AmmStatus::WaitingTrade),
+            _ => None,
+        }
+    }
+
+    /// Повторяет AmmStatus::swap_permission из Raydium AMM
+    pub fn swap_permission(&self) -> bool {
+        matches!(
+            self,
+            AmmStatus::Initialized | AmmStatus::SwapOnly | AmmStatus::WaitingTrade
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u64)]
+pub enum AmmState {
+    InvlidState = 0u64,
+    IdleState = 1u64,
+    CancelAllOrdersState = 2u64,
+    PlanOrdersState = 3u64,
+    CancelOrderState = 4u64,
+    PlaceOrdersState = 5u64,
+    PurgeOrderState = 6u64,
+}
@@ -1,4 +1,6 @@
 use crate::dex::{AmountInResult, AmountOutResult};
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u64)]
@@ -12,10 +14,14 @@ pub fn calc_total_without_take_pnl_no_orderbook(
     quote_reserve: u64,
     need_take_pnl_pc: u64,
     need_take_pnl_coin: u64,
-) -> (u64, u64) {
-    let total_coin_without_take_pnl = base_reserve.checked_sub(need_take_pnl_coin).unwrap();
-    let total_pc_without_take_pnl = quote_reserve.checked_sub(need_take_pnl_pc).unwrap();
-    (total_coin_without_take_pnl, total_pc_without_take_pnl)
+) -> Result<(u64, u64)> {
+    let total_coin_without_take_pnl = base_reserve
+        .checked_sub(need_take_pnl_coin)
+        .ok_or(ErrorCode::ReserveUnderflow)?;
+    let total_pc_without_take_pnl = quote_reserve
+        .checked_sub(need_take_pnl_pc)
+        .ok_or(ErrorCode::ReserveUnderflow)?;
+    Ok((total_coin_without_take_pnl, total_pc_without_take_pnl))
 }
 
 pub fn get_amount_out(
@@ -24,7 +30,7 @@ pub fn get_amount_out(
     amount_in: u64,
     fee_multiplier: u64,
     swap_for_quote: bool,
-) -> AmountOutResult {
+) -> Result<AmountOutResult> {
     // Определяем направление свапа
     let (input_reserve, output_reserve) = if swap_for_quote {
         (base_reserve, quote_reserve)
@@ -32,28 +38,38 @@ pub fn get_amount_out(
         (quote_reserve, base_reserve)
     };
 
-    let amount_out: u64;
-    let new_price: f64;
+    require!(
+        input_reserve != 0 && output_reserve != 0,
+        ErrorCode::ZeroReserve
+    );
 
-    let amount_in_with_fee = (amount_in as u128 * (10000 - fee_multiplier) as u128) / 10000;
+    let fee_denominator = 10000u64
+        .checked_sub(fee_multiplier)
+        .ok_or(ErrorCode::Overflow)?;
+    let amount_in_with_fee = (amount_in as u128 * fee_denominator as u128) / 10000;
 
     let numerator = output_reserve as u128 * amount_in_with_fee;
     let denominator = input_reserve as u128 + amount_in_with_fee;
-    amount_out = (numerator / denominator) as u64;
+    // amount_out < output_reserve, т.к. denominator > amount_in_with_fee
+    let amount_out = (numerator / denominator) as u64;
 
-    let new_input_reserve = input_reserve + amount_in;
+    let new_input_reserve = input_reserve
+        .checked_add(amount_in)
+        .ok_or(ErrorCode::Overflow)?;
     let new_output_reserve = output_reserve - amount_out;
 
-    new_price = (new_output_reserve as f64) / (new_input_reserve as f64);
+    let new_price = (new_output_reserve as f64) / (new_input_reserve as f64);
 
-    AmountOutResult {
+    Ok(AmountOutResult {
         amount_out,
+        consumed_in_amount: amount_in,
         new_price: if swap_for_quote {
             new_price
         } else {
             1.0 / new_price
         },
-    }
+        new_reserves: base_quote_reserves(new_input_reserve, new_output_reserve, swap_for_quote),
+    })
 }
 
 pub fn get_amount_in(
@@ -62,35 +78,61 @@ pub fn get_amount_in(
     amount_out: u64,
     fee_multiplier: u64,
     swap_for_quote: bool,
-) -> AmountInResult {
+) -> Result<AmountInResult> {
     let (input_reserve, output_reserve) = if swap_for_quote {
         (base_reserve, quote_reserve)
     } else {
         (quote_reserve, base_reserve)
     };
 
-    let amount_in: u64;
+    require!(
+        input_reserve != 0 && output_reserve != 0,
+        ErrorCode::ZeroReserve
+    );
+    require!(amount_out < output_reserve, ErrorCode::OutputExceedsReserve);
 
     let numerator = input_reserve as u128 * amount_out as u128;
     let denominator = (output_reserve - amount_out) as u128;
-    let amount_in_before_fee = (numerator + denominator - 1) / denominator;
-
-    let fee_denominator = 10000 - fee_multiplier;
-    amount_in = ((amount_in_before_fee * 10000 + fee_denominator as u128 - 1)
-        / fee_denominator as u128) as u64;
-
-    let new_input_reserve = input_reserve + amount_in;
+    let amount_in_before_fee = numerator.div_ceil(denominator);
+
+    let fee_denominator = 10000u64
+        .checked_sub(fee_multiplier)
+        .filter(|fee_denominator| *fee_denominator != 0)
+        .ok_or(ErrorCode::Overflow)?;
+    let amount_in: u64 = amount_in_before_fee
+        .checked_mul(10000)
+        .ok_or(ErrorCode::Overflow)?
+        .div_ceil(fee_denominator as u128)
+        .try_into()
+        .map_err(|_| ErrorCode::Overflow)?;
+
+    let new_input_reserve = input_reserve
+        .checked_add(amount_in)
+        .ok_or(ErrorCode::Overflow)?;
     let new_output_reserve = output_reserve - amount_out;
 
     let new_price = (new_output_reserve as f64) / (new_input_reserve as f64);
 
-    AmountInResult {
+    Ok(AmountInResult {
         amount_in,
         new_price: if swap_for_quote {
             new_price
         } else {
             1.0 / new_price
         },
+        new_reserves: base_quote_reserves(new_input_reserve, new_output_reserve, swap_for_quote),
+    })
+}
+
+fn base_quote_reserves(
+    input_reserve: u64,
+    output_reserve: u64,
+    swap_for_quote: bool,
+) -> (u128, u128) {
+    if swap_for_quote {
+        (input_reserve as u128, output_reserve as u128)
+    } else {
+        (output_reserve as u128, input_reserve as u128)
     }
 }
 
@@ -100,37 +142,37 @@ mod tests_get_quote_amount {
 
     #[test]
     fn test_1() {
-        let res = get_amount_out(12794417033873, 14623638931098, 4770695067, 25, true);
+        let res = get_amount_out(12794417033873, 14623638931098, 4770695067, 25, true).unwrap();
         assert_eq!(res.amount_out, 5437108676);
 
-        let res = get_amount_in(12794417033873, 14623638931098, 5437108676, 25, true);
+        let res = get_amount_in(12794417033873, 14623638931098, 5437108676, 25, true).unwrap();
         assert_eq!(res.amount_in, 4770695067);
     }
 
     #[test]
     fn test_2() {
-        let res = get_amount_out(67792550811113, 4347866560489, 13116944512, 25, true);
+        let res = get_amount_out(67792550811113, 4347866560489, 13116944512, 25, true).unwrap();
         assert_eq!(res.amount_out, 838988494);
 
-        // let res = get_amount_in(67792550811113, 4347866560489, 838988494, 25, true);
+        // let res = get_amount_in(67792550811113, 4347866560489, 838988494, 25, true).unwrap();
         // assert_eq!(res.amount_in, 13116944512);
     }
 
     #[test]
     fn test_3() {
-        let res = get_amount_out(16962266375499, 6206989776373, 32841092305, 25, true);
+        let res = get_amount_out(16962266375499, 6206989776373, 32841092305, 25, true).unwrap();
         assert_eq!(res.amount_out, 11964366572);
 
-        let res = get_amount_in(16962266375499, 6206989776373, 11964366572, 25, true);
+        let res = get_amount_in(16962266375499, 6206989776373, 11964366572, 25, true).unwrap();
         assert_eq!(res.amount_in, 32841092305);
     }
 
     #[test]
     fn test_4() {
-        let res = get_amount_out(40892586974517, 9310469542699, 39223577063, 25, true);
+        let res = get_amount_out(40892586974517, 9310469542699, 39223577063, 25, true).unwrap();
         assert_eq!(res.amount_out, 8899626294);
 
-        // let res = get_amount_in(16962266375499, 6206989776373, 11964366572, 25, true);
+        // let res = get_amount_in(16962266375499, 6206989776373, 11964366572, 25, true).unwrap();
         // assert_eq!(res.amount_in, 32841092305);
     }
 }
@@ -141,37 +183,37 @@ mod tests_get_token_amount {
 
     #[test]
     fn test_1() {
-        let res = get_amount_out(59363492683755, 4651763938687, 741384553, 25, false);
+        let res = get_amount_out(59363492683755, 4651763938687, 741384553, 25, false).unwrap();
         assert_eq!(res.amount_out, 9436027032);
 
-        let res = get_amount_in(59363492683755, 4651763938687, 9436027032, 25, false);
+        let res = get_amount_in(59363492683755, 4651763938687, 9436027032, 25, false).unwrap();
         assert_eq!(res.amount_in, 741384553);
     }
 
     #[test]
     fn test_2() {
-        let res = get_amount_out(39669574552562, 1213674483172, 1831981585, 25, false);
+        let res = get_amount_out(39669574552562, 1213674483172, 1831981585, 25, false).unwrap();
         assert_eq!(res.amount_out, 59639763742);
 
-        let res = get_amount_in(39669574552562, 1213674483172, 59639763742, 25, false);
+        let res = get_amount_in(39669574552562, 1213674483172, 59639763742, 25, false).unwrap();
         assert_eq!(res.amount_in, 1831981585);
     }
 
     #[test]
     fn test_3() {
-        let res = get_amount_out(168893908415298, 201910628112, 948713771, 25, false);
+        let res = get_amount_out(168893908415298, 201910628112, 948713771, 25, false).unwrap();
         assert_eq!(res.amount_out, 787901922402);
 
-        let res = get_amount_in(168893908415298, 201910628112, 787901922402, 25, false);
+        let res = get_amount_in(168893908415298, 201910628112, 787901922402, 25, false).unwrap();
         assert_eq!(res.amount_in, 948713771);
     }
 
     #[test]
     fn test_4() {
-        let res = get_amount_out(35100628106288, 10125606016962, 23886235154, 25, false);
+        let res = get_amount_out(35100628106288, 10125606016962, 23886235154, 25, false).unwrap();
         assert_eq!(res.amount_out, 82401237639);
 
-        let res = get_amount_in(35100628106288, 10125606016962, 82401237639, 25, false);
+        let res = get_amount_in(35100628106288, 10125606016962, 82401237639, 25, false).unwrap();
         assert_eq!(res.amount_in, 23886235154);
     }
 }
@@ -183,8 +225,85 @@ mod tests_calc_total_without_take_pnl_no_orderbook {
     #[test]
     fn test_1() {
         let (base_reserve_without_take_pnl, quote_reserve_without_take_pnl): (u64, u64) =
-            calc_total_without_take_pnl_no_orderbook(40892586974517, 9310469542699, 0, 0);
+            calc_total_without_take_pnl_no_orderbook(40892586974517, 9310469542699, 0, 0).unwrap();
         assert_eq!(base_reserve_without_take_pnl, 40892586974517);
         assert_eq!(quote_reserve_without_take_pnl, 9310469542699);
     }
 }
+
+#[cfg(test)]
+mod tests_edge_case_reserves {
+    use super::*;
+
+    #[test]
+    fn test_zero_reserve() {
+        assert_eq!(
+            get_amount_out(0, 1_000_000, 1000, 25, true).unwrap_err(),
+            ErrorCode::ZeroReserve.into()
+        );
+        assert_eq!(
+            get_amount_out(1_000_000, 0, 1000, 25, true).unwrap_err(),
+            ErrorCode::ZeroReserve.into()
+        );
+        assert_eq!(
+            get_amount_in(1_000_000, 0, 1000, 25, false).unwrap_err(),
+            ErrorCode::ZeroReserve.into()
+        );
+    }
+
+    #[test]
+    fn test_drained_pool() {
+        // выход больше или равен резерву
+        assert_eq!(
+            get_amount_in(1_000_000, 1_000_000, 1_000_000, 25, true).unwrap_err(),
+            ErrorCode::OutputExceedsReserve.into()
+        );
+        assert_eq!(
+            get_amount_in(1_000_000, 1_000_000, u64::MAX, 25, false).unwrap_err(),
+            ErrorCode::OutputExceedsReserve.into()
+        );
+
+        // почти пустой пул: выход никогда не превышает резерв
+        let res = get_amount_out(1_000_000, 1, u64::MAX / 2, 25, true).unwrap();
+        assert_eq!(res.amount_out, 0);
+    }
+
+    #[test]
+    fn test_max_reserves() {
+        let res = get_amount_out(u64::MAX / 2, u64::MAX, 1_000_000, 25, true).unwrap();
+        assert!(res.amount_out > 0);
+
+        assert_eq!(
+            get_amount_out(u64::MAX, u64::MAX, 1, 25, true).unwrap_err(),
+            ErrorCode::Overflow.into()
+        );
+        assert_eq!(
+            get_amount_in(u64::MAX, u64::MAX, u64::MAX - 1, 25, true).unwrap_err(),
+            ErrorCode::Overflow.into()
+        );
+    }
+
+    #[test]
+    fn test_invalid_fee() {
+        assert_eq!(
+            get_amount_out(1_000_000, 1_000_000, 1000, 10001, true).unwrap_err(),
+            ErrorCode::Overflow.into()
+        );
+        assert_eq!(
+            get_amount_in(1_000_000, 1_000_000, 1000, 10000, true).unwrap_err(),
+            ErrorCode::Overflow.into()
+        );
+    }
+
+    #[test]
+    fn test_take_pnl_underflow() {
+        assert_eq!(
+            calc_total_without_take_pnl_no_orderbook(100, 100, 0, 101).unwrap_err(),
+            ErrorCode::ReserveUnderflow.into()
+        );
+        assert_eq!(
+            calc_total_without_take_pnl_no_orderbook(100, 100, 101, 0).unwrap_err(),
+            ErrorCode::ReserveUnderflow.into()
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::arbitrage_engine::{BasePool, LiquidityType, SwapResult};
-use crate::commons::get_ata_balance;
+use crate::arbitrage_engine::{BasePool, LiquidityType, PoolKind, SwapResult};
+use crate::commons::{get_ata_balance, SwapAuthority};
 use crate::dex::raydium_amm::*;
 use crate::error::ErrorCode;
 use crate::state::*;
@@ -13,6 +13,7 @@ pub struct RaydiumAmmPool<'b, 'info> {
     pub base_reserve_without_take_pnl: u64,
     pub quote_reserve_without_take_pnl: u64,
 
+    pub authority: SwapAuthority<'b, 'info>,
     pub accounts: &'b RaydiumAmmAccounts<'info>,
     pub common: &'b CommonAccounts<'info>,
 }
@@ -21,12 +22,31 @@ impl<'b, 'info> RaydiumAmmPool<'b, 'info> {
     pub fn new(
         common: &'b CommonAccounts<'info>,
         accounts: &'b RaydiumAmmAccounts<'info>,
+        authority: SwapAuthority<'b, 'info>,
     ) -> Result<Self> {
         // Десериализуем данные из аккаунта AMM для проверки
         let amm_data = accounts.amm_info.try_borrow_data()?;
+
+        // Пул должен принимать свапы так же, как это проверяет сам Raydium
+        let (status, state, pool_open_time) = AmmInfo::deserialize_status(&amm_data)?;
+        check_swap_allowed(status, state, pool_open_time, Clock::get()?.unix_timestamp)?;
+
         let (coin_mint, pc_mint) = AmmInfo::deserialize_mints(&amm_data)?;
         let (swap_fee_numerator, _) = AmmInfo::deserialize_swap_fees(&amm_data)?;
         let (need_take_pnl_coin, need_take_pnl_pc) = AmmInfo::deserialize_need_take_pnl(&amm_data)?;
+        let (coin_vault, pc_vault) = AmmInfo::deserialize_vaults(&amm_data)?;
+
+        // Резервы читаются только из vault-ов, записанных в AmmInfo
+        require_keys_eq!(
+            accounts.coin_vault.key(),
+            coin_vault,
+            ErrorCode::RaydiumCoinVaultMismatch
+        );
+        require_keys_eq!(
+            accounts.pc_vault.key(),
+            pc_vault,
+            ErrorCode::RaydiumPcVaultMismatch
+        );
 
         // Проверяем, что оба токена присутствуют в пуле
         let source_in_pool = common.user_source_token_mint.key == &coin_mint
@@ -59,7 +79,7 @@ impl<'b, 'info> RaydiumAmmPool<'b, 'info> {
                 quote_reserve,
                 need_take_pnl_pc,
                 need_take_pnl_coin,
-            );
+            )?;
 
         let swap_fee = swap_fee_numerator;
 
@@ -76,6 +96,7 @@ impl<'b, 'info> RaydiumAmmPool<'b, 'info> {
             quote_reserve,
             base_reserve_without_take_pnl,
             quote_reserve_without_take_pnl,
+            authority,
             accounts,
             common,
         })
@@ -98,15 +119,48 @@ impl<'b, 'info> RaydiumAmmPool<'b, 'info> {
     }
 }
 
+pub fn check_swap_allowed(
+    status: u64,
+    state: u64,
+    pool_open_time: u64,
+    unix_timestamp: i64,
+) -> Result<()> {
+    let amm_status = AmmStatus::from_u64(status).ok_or(ErrorCode::RaydiumPoolSwapDisabled)?;
+
+    require!(
+        amm_status.swap_permission(),
+        ErrorCode::RaydiumPoolSwapDisabled
+    );
+    require!(
+        state != AmmState::InvlidState as u64,
+        ErrorCode::RaydiumPoolInvalidState
+    );
+
+    // WaitingTrade разрешает свапы начиная с pool_open_time: Raydium отклоняет только now < open_time
+    if amm_status == AmmStatus::WaitingTrade {
+        require!(
+            unix_timestamp as u64 >= pool_open_time,
+            ErrorCode::RaydiumPoolNotOpen
+        );
+    }
+
+    Ok(())
+}
+
 impl<'b, 'info> BasePool for RaydiumAmmPool<'b, 'info> {
     fn get_fee_rate_f64(&self) -> f64 {
         let fee_denominator = 10000.0;
         let fee_multiplier = fee_denominator - (self.swap_fee as f64);
 
-        1.0 - ((fee_multiplier as f64) / (fee_denominator as f64))
+        1.0 - (fee_multiplier / fee_denominator)
     }
 
     fn get_price(&self) -> Result<f64> {
+        require!(
+            self.base_reserve_without_take_pnl != 0 && self.quote_reserve_without_take_pnl != 0,
+            ErrorCode::ZeroReserve
+        );
+
         Ok(self.normalize_price(
             self.quote_reserve_without_take_pnl as f64 / self.base_reserve_without_take_pnl as f64,
         ))
@@ -120,13 +174,14 @@ impl<'b, 'info> BasePool for RaydiumAmmPool<'b, 'info> {
             amount_in,
             self.swap_fee,
             normalized_swap_for_quote,
-        );
+        )?;
 
         let res = SwapResult {
             amount_out: result.amount_out,
             consumed_in_amount: amount_in,
             is_fully_filled: true,
             new_price: self.normalize_price(result.new_price),
+            crosses_fee_tier: false,
         };
 
         Ok(res)
@@ -141,13 +196,14 @@ impl<'b, 'info> BasePool for RaydiumAmmPool<'b, 'info> {
             amount_out,
             self.swap_fee,
             normalized_swap_for_quote,
-        );
+        )?;
 
         Ok(SwapResult {
             amount_out,
             consumed_in_amount: result.amount_in,
             is_fully_filled: true,
             new_price: self.normalize_price(result.new_price),
+            crosses_fee_tier: false,
         })
     }
 
@@ -155,6 +211,14 @@ impl<'b, 'info> BasePool for RaydiumAmmPool<'b, 'info> {
         Ok(LiquidityType::Constant)
     }
 
+    fn get_pool_kind(&self) -> PoolKind {
+        PoolKind::RaydiumAmmV4
+    }
+
+    fn get_pool_key(&self) -> Pubkey {
+        self.accounts.amm_info.key()
+    }
+
     fn get_base_quote_product(&self) -> Result<u128> {
         Ok(
             self.base_reserve_without_take_pnl as u128
@@ -162,6 +226,13 @@ impl<'b, 'info> BasePool for RaydiumAmmPool<'b, 'info> {
         )
     }
 
+    fn get_reserves(&self) -> Result<(u64, u64)> {
+        Ok((
+            self.base_reserve_without_take_pnl,
+            self.quote_reserve_without_take_pnl,
+        ))
+    }
+
     fn get_max_amount(&self, _price_delta: f64, _swap_for_quote: bool) -> Result<u64> {
         Ok(u64::MAX)
     }
@@ -204,9 +275,62 @@ impl<'b, 'info> BasePool for RaydiumAmmPool<'b, 'info> {
             &self.accounts.amm_info, // market_vault_signer
             user_token_in,
             user_token_out,
-            &self.common.user,
+            &self.authority,
             amount_in,
             min_amount_out,
         )
     }
 }
+
+#[cfg(test)]
+mod tests_check_swap_allowed {
+    use super::*;
+
+    const IDLE: u64 = AmmState::IdleState as u64;
+
+    #[test]
+    fn test_status() {
+        assert!(check_swap_allowed(AmmStatus::Initialized as u64, IDLE, 0, 100).is_ok());
+        assert!(check_swap_allowed(AmmStatus::SwapOnly as u64, IDLE, 0, 100).is_ok());
+
+        assert_eq!(
+            check_swap_allowed(AmmStatus::Disabled as u64, IDLE, 0, 100).unwrap_err(),
+            ErrorCode::RaydiumPoolSwapDisabled.into()
+        );
+        assert_eq!(
+            check_swap_allowed(AmmStatus::WithdrawOnly as u64, IDLE, 0, 100).unwrap_err(),
+            ErrorCode::RaydiumPoolSwapDisabled.into()
+        );
+        assert_eq!(
+            check_swap_allowed(42, IDLE, 0, 100).unwrap_err(),
+            ErrorCode::RaydiumPoolSwapDisabled.into()
+        );
+    }
+
+    #[test]
+    fn test_state() {
+        assert_eq!(
+            check_swap_allowed(
+                AmmStatus::Initialized as u64,
+                AmmState::InvlidState as u64,
+                0,
+                100
+            )
+            .unwrap_err(),
+            ErrorCode::RaydiumPoolInvalidState.into()
+        );
+    }
+
+    #[test]
+    fn test_pool_open_time() {
+        let waiting = AmmStatus::WaitingTrade as u64;
+
+        assert!(check_swap_allowed(waiting, IDLE, 99, 100).is_ok());
+        // секунда открытия уже торгуется
+        assert!(check_swap_allowed(waiting, IDLE, 100, 100).is_ok());
+        assert_eq!(
+            check_swap_allowed(waiting, IDLE, 101, 100).unwrap_err(),
+            ErrorCode::RaydiumPoolNotOpen.into()
+        );
+    }
+}
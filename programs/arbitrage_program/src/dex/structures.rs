@@ -1,11 +1,17 @@
 #[derive(Debug, Clone, Copy)]
 pub struct AmountOutResult {
     pub amount_out: u64,
+    // Сколько входа реально спишется: может быть меньше amount_in, если выход дискретен
+    pub consumed_in_amount: u64,
     pub new_price: f64,
+    // Резервы пула (base, quote) после сделки
+    pub new_reserves: (u128, u128),
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct AmountInResult {
     pub amount_in: u64,
     pub new_price: f64,
+    // Резервы пула (base, quote) после сделки
+    pub new_reserves: (u128, u128),
 }
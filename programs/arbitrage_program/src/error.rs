@@ -28,9 +28,224 @@ pub enum ErrorCode {
 
     #[msg("Arbitrage verification failed")]
     ArbitrageVerificationFailed,
+
+    // Коды выше совпадают с прежними версиями, по ним матчатся боты и индексаторы.
+    // Новые варианты добавляются только в конец
+    #[msg("Reserve underflow")]
+    ReserveUnderflow,
+
+    #[msg("Pool reserve is zero")]
+    ZeroReserve,
+
+    #[msg("Requested output exceeds pool reserve")]
+    OutputExceedsReserve,
+
+    #[msg("Profit does not fit into i64")]
+    ProfitOverflow,
+
+    #[msg("Quote expired: current slot is past max_slot")]
+    QuoteExpired,
+
+    #[msg("Pool reserves drifted beyond tolerance")]
+    ReserveDriftExceeded,
+
+    #[msg("Signer is not authorized")]
+    Unauthorized,
+
+    #[msg("Program is paused")]
+    ProgramPaused,
+
+    #[msg("Instruction is disabled in config")]
+    InstructionDisabled,
+
+    #[msg("Operator is not in the allowlist")]
+    UnauthorizedOperator,
+
+    #[msg("Too many operators")]
+    TooManyOperators,
+
+    #[msg("Vault and vault authority must be passed together and match")]
+    InvalidVaultAuthority,
+
+    #[msg("Profit split shares exceed 100%")]
+    InvalidProfitSplit,
+
+    #[msg("Profit split account is missing or does not match config")]
+    InvalidProfitSplitAccount,
+
+    #[msg("Tip account is required when tip mode is enabled")]
+    MissingTipAccount,
+
+    #[msg("Unwrap token account is required to pay the tip from WSOL profit")]
+    MissingUnwrapAccount,
+
+    #[msg("Tip exceeds realized profit")]
+    TipExceedsProfit,
+
+    #[msg("Lamport tips are only supported with a native SOL source")]
+    TipRequiresNativeSource,
+
+    #[msg("Operator is in cool-down after failed trades")]
+    RiskCooldownActive,
+
+    #[msg("Operator cumulative loss limit exceeded")]
+    RiskLossLimitExceeded,
+
+    #[msg("Operator notional limit for the window exceeded")]
+    RiskNotionalLimitExceeded,
+
+    #[msg("Mint is not owned by Token or Token-2022 program")]
+    InvalidTokenProgram,
+
+    #[msg("Intermediate mint has a freeze authority")]
+    MintHasFreezeAuthority,
+
+    #[msg("Intermediate mint has a transfer hook")]
+    MintHasTransferHook,
+
+    #[msg("Intermediate mint has a permanent delegate")]
+    MintHasPermanentDelegate,
+
+    #[msg("Intermediate mint is non-transferable")]
+    MintNonTransferable,
+
+    #[msg("Intermediate mint is pausable")]
+    MintPausable,
+
+    #[msg("Intermediate mint creates frozen accounts by default")]
+    MintDefaultStateFrozen,
+
+    #[msg("wrap_sol requires the native mint as source")]
+    WrapSolRequiresNativeMint,
+
+    #[msg("wrap_sol is not available in vault mode")]
+    WrapSolRequiresUserAuthority,
+
+    #[msg("Instructions sysvar is required in flash-loan mode")]
+    FlashLoanSysvarMissing,
+
+    #[msg("No flash borrow into the source token account before this instruction")]
+    FlashLoanBorrowNotFound,
+
+    #[msg("No matching flash repay after this instruction")]
+    FlashLoanRepayNotFound,
+
+    #[msg("Pair stats account does not match the pools or operator")]
+    InvalidPairStatsAccount,
+
+    #[msg("Both arbitrage legs use the same pool")]
+    SamePoolLegs,
+
+    #[msg("Pool account is not owned by a supported DEX or has an unexpected layout")]
+    UnsupportedPoolAccount,
+
+    #[msg("Detected pool kind does not match the requested one")]
+    PoolKindMismatch,
+
+    #[msg("Not enough remaining accounts for the pool leg")]
+    PoolLegAccountsMissing,
+
+    #[msg("Pumpswap creator vault does not match pool coin_creator")]
+    PumpswapCreatorVaultMismatch,
+
+    #[msg("Pumpswap account does not match its derived PDA")]
+    PumpswapDerivedAccountMismatch,
+
+    #[msg("Derived Pumpswap account is missing from remaining accounts")]
+    PumpswapDerivedAccountMissing,
+
+    #[msg("Sizing hint is outside the pool amount bounds")]
+    SizingHintOutOfBounds,
+
+    #[msg("Available capital is below min_amount")]
+    CapitalBelowMinAmountIn,
+
+    #[msg("Too many assets in arbitrage context snapshot")]
+    ContextAssetLimitExceeded,
+
+    #[msg("Context asset accounts do not match the snapshot")]
+    ContextAssetMismatch,
+
+    #[msg("Asset balance rule violated")]
+    ContextAssetRuleViolated,
+
+    #[msg("Unsupported arbitrage context version")]
+    ContextVersionMismatch,
+
+    #[msg("Matching verify_arbitrage_context instruction not found later in transaction")]
+    MissingVerifyInstruction,
+
+    #[msg("Arbitrage context was not initialized in this transaction")]
+    ContextNotInitializedInTransaction,
+
+    #[msg("Arbitrage context account has an unknown layout")]
+    InvalidArbContextAccount,
+
+    #[msg("Unknown DEX program id")]
+    InvalidDexProgramId,
+
+    #[msg("Account is not owned by the expected program")]
+    InvalidAccountOwner,
+
+    #[msg("Raydium AMM coin vault does not match pool state")]
+    RaydiumCoinVaultMismatch,
+
+    #[msg("Raydium AMM pc vault does not match pool state")]
+    RaydiumPcVaultMismatch,
+
+    #[msg("Pumpswap base mint does not match pool state")]
+    PumpswapBaseMintMismatch,
+
+    #[msg("Pumpswap quote mint does not match pool state")]
+    PumpswapQuoteMintMismatch,
+
+    #[msg("Pumpswap pool base token account does not match pool state")]
+    PumpswapBaseVaultMismatch,
+
+    #[msg("Pumpswap pool quote token account does not match pool state")]
+    PumpswapQuoteVaultMismatch,
+
+    #[msg("Raydium AMM pool status does not allow swaps")]
+    RaydiumPoolSwapDisabled,
+
+    #[msg("Raydium AMM pool is in invalid state")]
+    RaydiumPoolInvalidState,
+
+    #[msg("Raydium AMM pool is not open yet")]
+    RaydiumPoolNotOpen,
+
+    #[msg("Pumpswap buy is disabled in global config")]
+    PumpswapBuyDisabled,
+
+    #[msg("Pumpswap sell is disabled in global config")]
+    PumpswapSellDisabled,
+
+    #[msg("Operator is not allowed to trade from this vault")]
+    VaultOperatorNotAllowed,
+
+    #[msg("Token account is not owned by the vault authority or has a wrong mint")]
+    InvalidVaultTokenAccount,
+
+    #[msg("Tip unwrap account must be an empty WSOL account of the swap authority")]
+    InvalidTipUnwrapAccount,
+
+    #[msg("Flash loan fee_bps is below the minimum in config")]
+    FlashLoanFeeBelowMinimum,
     // #[msg("Division by zero")]
     // DivisionByZero,
 
     // #[msg("Missing Raydium accounts")]
     // MissingRaydiumAmmAccounts,
 }
+
+#[cfg(test)]
+mod tests_error_codes {
+    use super::*;
+
+    #[test]
+    fn test_baseline_codes_unchanged() {
+        assert_eq!(u32::from(ErrorCode::InsufficientLiquidity), 6000);
+        assert_eq!(u32::from(ErrorCode::Overflow), 6007);
+        assert_eq!(u32::from(ErrorCode::ArbitrageVerificationFailed), 6008);
+    }
+}
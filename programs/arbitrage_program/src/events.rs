@@ -0,0 +1,36 @@
+use crate::arbitrage_engine::PoolKind;
+use anchor_lang::prelude::*;
+
+/// Исполненный арбитраж: котировка on-chain поиска и фактический результат
+#[event]
+pub struct ArbExecuted {
+    pub user: Pubkey,
+    pub pool_a: Pubkey,
+    pub pool_b: Pubkey,
+    pub dex_a: PoolKind,
+    pub dex_b: PoolKind,
+    pub direction_a_to_b: bool,
+    pub amount_in: u64,
+    pub intermediate_amount: u64,
+    // Прибыль по котировке find_optimal_amount и по изменению баланса source (за вычетом займа)
+    pub predicted_profit: i64,
+    pub realized_profit: i64,
+    pub price_delta_bps: u64,
+    // Сделка переводит один из пулов в другой тир комиссий Pumpswap
+    pub crosses_fee_tier: bool,
+    pub iterations: u32,
+    pub slot: u64,
+}
+
+/// Исполненный одиночный своп
+#[event]
+pub struct SwapExecuted {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub dex: PoolKind,
+    pub source_to_intermediate: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    // Фактически полученная сумма по балансу выходного аккаунта
+    pub amount_out: u64,
+}
@@ -1,20 +1,48 @@
 use crate::arbitrage_engine::{BasePool, BaseSwapParams};
-use crate::commons::create_ata_if_missing;
+use crate::commons::{
+    check_wrap_sol_allowed, create_ata_if_missing, get_ata_balance, resolve_token_program,
+    screen_mint, unwrap_sol, wrap_sol, SwapAuthority,
+};
 use crate::dex::raydium_amm::raydium_amm_pool::RaydiumAmmPool;
+use crate::events::SwapExecuted;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RaydiumAmmSwap<'info> {
     pub common: CommonAccounts<'info>,
     pub raydium_amm: RaydiumAmmAccounts<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub vault_signer: VaultSignerAccounts<'info>,
 }
 
 pub fn raydium_amm_swap<'b, 'info>(
     ctx: Context<'_, 'b, '_, 'info, RaydiumAmmSwap<'info>>,
     params: BaseSwapParams,
 ) -> Result<()> {
-    let raydium_amm_pool = RaydiumAmmPool::new(&ctx.accounts.common, &ctx.accounts.raydium_amm)?;
+    ctx.accounts
+        .config
+        .check_access(ctx.accounts.common.user.key, IX_RAYDIUM_AMM_SWAP)?;
+
+    if let (true, Some(screening)) = (params.source_to_intermediate, &params.mint_screening) {
+        screen_mint(&ctx.accounts.common.user_intermediate_token_mint, screening)?;
+    }
+
+    let authority = SwapAuthority::resolve(&ctx.accounts.common, &ctx.accounts.vault_signer)?;
+
+    if params.wrap_sol {
+        check_wrap_sol_allowed(&ctx.accounts.common, &authority)?;
+    }
+
+    let raydium_amm_pool = RaydiumAmmPool::new(
+        &ctx.accounts.common,
+        &ctx.accounts.raydium_amm,
+        authority.clone(),
+    )?;
 
     let test_result =
         raydium_amm_pool.get_amount_out(params.amount_in, !params.source_to_intermediate)?;
@@ -35,18 +63,56 @@ pub fn raydium_amm_swap<'b, 'info>(
 
     create_ata_if_missing(
         &ctx.accounts.common.user,
+        &authority.to_account_info(),
         &ctx.accounts.common.user_intermediate_token_account,
         &ctx.accounts.common.user_intermediate_token_mint,
         &ctx.accounts.common.system_program,
-        &ctx.accounts.common.token_program,
+        &resolve_token_program(
+            &ctx.accounts.common.user_intermediate_token_mint,
+            &ctx.accounts.common,
+        )?,
         &ctx.accounts.common.associated_token_program,
     )?;
 
+    // При продаже за SOL wrap_sol только создаёт пустой WSOL ATA для выхода
+    if params.wrap_sol {
+        let wrap_amount = if params.source_to_intermediate {
+            params.amount_in
+        } else {
+            0
+        };
+        wrap_sol(&ctx.accounts.common, &authority, wrap_amount)?;
+    }
+
+    // Фактический выход - по изменению баланса выходного аккаунта до закрытия WSOL ATA
+    let destination = if params.source_to_intermediate {
+        &ctx.accounts.common.user_intermediate_token_account
+    } else {
+        &ctx.accounts.common.user_source_token_account
+    };
+    let destination_balance_before = get_ata_balance(destination)?;
+
     raydium_amm_pool.swap(
         params.amount_in,
         params.min_amount_out,
         params.source_to_intermediate,
     )?;
 
+    let amount_out = get_ata_balance(destination)?.saturating_sub(destination_balance_before);
+
+    emit_cpi!(SwapExecuted {
+        user: ctx.accounts.common.user.key(),
+        pool: raydium_amm_pool.get_pool_key(),
+        dex: raydium_amm_pool.get_pool_kind(),
+        source_to_intermediate: params.source_to_intermediate,
+        amount_in: params.amount_in,
+        min_amount_out: params.min_amount_out,
+        amount_out,
+    });
+
+    if params.wrap_sol {
+        unwrap_sol(&ctx.accounts.common)?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,2 @@
+pub mod two_pool_arb;
+pub use two_pool_arb::*;
@@ -0,0 +1,108 @@
+use crate::arbitrage_engine::{
+    check_quote_deadline, PoolKind, PumpswapAccountsMode, UniversalOptimalAmountParams,
+};
+use crate::commons::{check_wrap_sol_allowed, execute_arb, screen_mint, ArbAccounts};
+use crate::dex::PoolAccounts;
+use crate::error::ErrorCode;
+use crate::events::ArbExecuted;
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+/// Арбитраж между двумя любыми поддерживаемыми пулами.
+/// Аккаунты ног передаются в remaining_accounts: сначала пул A, затем пул B, каждый в порядке
+/// полей своей структуры (PumpswapAccounts / RaydiumAmmAccounts). DEX ноги определяется по
+/// аккаунту пула, pool_kind_a / pool_kind_b нужны только для дополнительной проверки.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TwoPoolArb<'info> {
+    pub common: CommonAccounts<'info>,
+    pub arb: ArbSharedAccounts<'info>,
+}
+
+pub fn two_pool_arb<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TwoPoolArb<'info>>,
+    pool_kind_a: Option<PoolKind>,
+    pool_kind_b: Option<PoolKind>,
+    params: UniversalOptimalAmountParams,
+) -> Result<()> {
+    ctx.accounts
+        .arb
+        .config
+        .check_access(ctx.accounts.common.user.key, IX_TWO_POOL_ARB)?;
+
+    if let Some(screening) = &params.mint_screening {
+        screen_mint(&ctx.accounts.common.user_intermediate_token_mint, screening)?;
+    }
+
+    let accounts = ArbAccounts::new(&ctx.accounts.common, &mut ctx.accounts.arb)?;
+
+    if params.wrap_sol {
+        check_wrap_sol_allowed(&ctx.accounts.common, &accounts.authority)?;
+    }
+
+    let slot = Clock::get()?.slot;
+    check_quote_deadline(slot, params.max_slot)?;
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let mut pool_accounts_a = PoolAccounts::try_from_remaining(
+        pool_kind_a,
+        params.pumpswap_accounts,
+        ctx.program_id,
+        &mut remaining_accounts,
+    )?;
+    let mut pool_accounts_b = PoolAccounts::try_from_remaining(
+        pool_kind_b,
+        params.pumpswap_accounts,
+        ctx.program_id,
+        &mut remaining_accounts,
+    )?;
+
+    // Выведенные аккаунты slim-ног Pumpswap лежат после обеих ног
+    let user = accounts.authority.key();
+    pool_accounts_a.fill_derived(&ctx.accounts.common, &user, remaining_accounts)?;
+    pool_accounts_b.fill_derived(&ctx.accounts.common, &user, remaining_accounts)?;
+
+    // Дополненные ноги выведены on-chain, сверять нужно только переданные целиком
+    let verify_pumpswap_pdas = params.pumpswap_accounts == PumpswapAccountsMode::Verified;
+
+    let pool_a = pool_accounts_a.build_pool(
+        &ctx.accounts.common,
+        accounts.authority.clone(),
+        verify_pumpswap_pdas,
+    )?;
+    let pool_b = pool_accounts_b.build_pool(
+        &ctx.accounts.common,
+        accounts.authority.clone(),
+        verify_pumpswap_pdas,
+    )?;
+
+    require_keys_neq!(
+        pool_a.get_pool_key(),
+        pool_b.get_pool_key(),
+        ErrorCode::SamePoolLegs
+    );
+
+    let execution = execute_arb(accounts, pool_a.as_ref(), pool_b.as_ref(), &params, slot)?;
+    let result = &execution.result;
+
+    emit_cpi!(ArbExecuted {
+        user: ctx.accounts.common.user.key(),
+        pool_a: pool_a.get_pool_key(),
+        pool_b: pool_b.get_pool_key(),
+        dex_a: pool_a.get_pool_kind(),
+        dex_b: pool_b.get_pool_kind(),
+        direction_a_to_b: result.direction_a_to_b,
+        amount_in: result.amount_in,
+        intermediate_amount: result.intermediate_amount,
+        predicted_profit: result.profit,
+        realized_profit: execution.realized_profit,
+        price_delta_bps: result.price_delta_bps,
+        crosses_fee_tier: result.crosses_fee_tier,
+        iterations: result.iterations,
+        slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::error::ErrorCode;
+use crate::program::ArbitrageProgram;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CONFIG_SEED],
+        bump,
+        space = 8 + Config::INIT_SPACE,
+    )]
+    pub config: Account<'info, Config>,
+
+    // Конфиг может создать только upgrade authority программы
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, ArbitrageProgram>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    params: InitializeConfigParams,
+) -> Result<()> {
+    require!(
+        params.operators.len() <= MAX_OPERATORS,
+        ErrorCode::TooManyOperators
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    config.bump = ctx.bumps.config;
+    config.admin = ctx.accounts.admin.key();
+    config.paused = false;
+    config.enabled_instructions = params.enabled_instructions;
+    config.operators = params.operators;
+    // распределение прибыли выключено, пока админ не задаст его через update_config
+    config.profit_split = ProfitSplit::default();
+    config.risk_limits = RiskLimits::default();
+    config.min_flash_loan_fee_bps = 0;
+
+    Ok(())
+}
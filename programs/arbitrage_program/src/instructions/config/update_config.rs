@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn update_config(ctx: Context<ConfigAdminAccounts>, params: UpdateConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    if let Some(enabled_instructions) = params.enabled_instructions {
+        config.enabled_instructions = enabled_instructions;
+    }
+
+    if let Some(operators) = params.operators {
+        require!(
+            operators.len() <= MAX_OPERATORS,
+            ErrorCode::TooManyOperators
+        );
+        config.operators = operators;
+    }
+
+    if let Some(profit_split) = params.profit_split {
+        profit_split.validate()?;
+        config.profit_split = profit_split;
+    }
+
+    if let Some(risk_limits) = params.risk_limits {
+        config.risk_limits = risk_limits;
+    }
+
+    if let Some(min_flash_loan_fee_bps) = params.min_flash_loan_fee_bps {
+        config.min_flash_loan_fee_bps = min_flash_loan_fee_bps;
+    }
+
+    Ok(())
+}
+
+pub fn rotate_admin(ctx: Context<ConfigAdminAccounts>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.config.admin = new_admin;
+
+    Ok(())
+}
+
+pub fn set_paused(ctx: Context<ConfigAdminAccounts>, paused: bool) -> Result<()> {
+    ctx.accounts.config.paused = paused;
+
+    Ok(())
+}
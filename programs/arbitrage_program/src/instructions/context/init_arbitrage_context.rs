@@ -2,8 +2,9 @@ use crate::commons::init_arbitrage_context;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
-pub fn init_arbitrage_context_instruction(
-    ctx: Context<InitArbitrageContextAccounts>,
+pub fn init_arbitrage_context_instruction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitArbitrageContextAccounts<'info>>,
+    params: InitArbitrageContextParams,
 ) -> Result<()> {
-    init_arbitrage_context(ctx)
+    init_arbitrage_context(ctx, params)
 }
@@ -0,0 +1,15 @@
+use crate::commons::{close_arbitrage_context, migrate_arbitrage_context};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn migrate_arbitrage_context_instruction(
+    ctx: Context<MigrateArbitrageContextAccounts>,
+) -> Result<()> {
+    migrate_arbitrage_context(ctx)
+}
+
+pub fn close_arbitrage_context_instruction(
+    ctx: Context<CloseArbitrageContextAccounts>,
+) -> Result<()> {
+    close_arbitrage_context(ctx)
+}
@@ -3,3 +3,6 @@ pub use init_arbitrage_context::*;
 
 pub mod verify_arbitrage_context;
 pub use verify_arbitrage_context::*;
+
+pub mod migrate_arbitrage_context;
+pub use migrate_arbitrage_context::*;
@@ -2,6 +2,8 @@ use crate::commons::verify_arbitrage_context;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
-pub fn verify_arbitrage_context_instruction(ctx: Context<VerifyArbitrageAccounts>) -> Result<()> {
+pub fn verify_arbitrage_context_instruction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VerifyArbitrageAccounts<'info>>,
+) -> Result<()> {
     verify_arbitrage_context(ctx)
 }
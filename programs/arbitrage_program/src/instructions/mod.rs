@@ -6,3 +6,18 @@ pub use amm::*;
 
 pub mod context;
 pub use context::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod vault;
+pub use vault::*;
+
+pub mod risk;
+pub use risk::*;
+
+pub mod stats;
+pub use stats::*;
+
+pub mod arb;
+pub use arb::*;
@@ -1,56 +1,80 @@
-use std::u64;
-
-use crate::arbitrage_engine::{
-    find_optimal_amount, BasePool, ComputedParams, UniversalOptimalAmountParams,
-};
-use crate::commons::{create_ata_if_missing, get_ata_balance};
+use crate::arbitrage_engine::{check_quote_deadline, BasePool, UniversalOptimalAmountParams};
+use crate::commons::{check_wrap_sol_allowed, execute_arb, screen_mint, ArbAccounts};
 use crate::dex::pumpswap::pumpswap_pool::PumpswapPool;
 use crate::dex::raydium_amm::raydium_amm_pool::RaydiumAmmPool;
+use crate::events::ArbExecuted;
 
 use anchor_lang::prelude::*;
 
 use crate::state::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct PumpswapAmmArb<'info> {
     pub common: CommonAccounts<'info>,
     pub pumpswap: PumpswapAccounts<'info>,
     pub amm: RaydiumAmmAccounts<'info>,
+
+    pub arb: ArbSharedAccounts<'info>,
 }
 
 pub fn pumpswap_amm_arb<'b, 'info>(
     ctx: Context<'_, 'b, '_, 'info, PumpswapAmmArb<'info>>,
     params: UniversalOptimalAmountParams,
 ) -> Result<()> {
+    ctx.accounts
+        .arb
+        .config
+        .check_access(ctx.accounts.common.user.key, IX_PUMPSWAP_AMM_ARB)?;
+
+    if let Some(screening) = &params.mint_screening {
+        screen_mint(&ctx.accounts.common.user_intermediate_token_mint, screening)?;
+    }
+
+    let accounts = ArbAccounts::new(&ctx.accounts.common, &mut ctx.accounts.arb)?;
+
+    if params.wrap_sol {
+        check_wrap_sol_allowed(&ctx.accounts.common, &accounts.authority)?;
+    }
+
+    // Котировка устарела - выходим до чтения пулов
+    let slot = Clock::get()?.slot;
+    check_quote_deadline(slot, params.max_slot)?;
+
     // Создаем экземпляр PumpswapPool
-    let pumpswap_pool = PumpswapPool::new(&ctx.accounts.common, &ctx.accounts.pumpswap)?;
+    let pumpswap_pool = PumpswapPool::new(
+        &ctx.accounts.common,
+        &ctx.accounts.pumpswap,
+        accounts.authority.clone(),
+        params.pumpswap_accounts.verifies_passed(),
+    )?;
 
     // Создаем экземпляр RaydiumAmmPool
-    let amm_pool = RaydiumAmmPool::new(&ctx.accounts.common, &ctx.accounts.amm)?;
-
-    let max_amount_in = get_ata_balance(&ctx.accounts.common.user_source_token_account)?;
-    let max_amount_in = max_amount_in * 99 / 100;
-    let computed_params = ComputedParams { max_amount_in };
-
-    let result = find_optimal_amount(&pumpswap_pool, &amm_pool, &params, &computed_params)?;
-
-    // Создаем ATA, если не существует
-    create_ata_if_missing(
-        &ctx.accounts.common.user,
-        &ctx.accounts.common.user_intermediate_token_account,
-        &ctx.accounts.common.user_intermediate_token_mint,
-        &ctx.accounts.common.system_program,
-        &ctx.accounts.common.token_program,
-        &ctx.accounts.common.associated_token_program,
+    let amm_pool = RaydiumAmmPool::new(
+        &ctx.accounts.common,
+        &ctx.accounts.amm,
+        accounts.authority.clone(),
     )?;
 
-    if result.direction_a_to_b {
-        pumpswap_pool.swap(u64::MAX, result.intermediate_amount, true)?;
-        amm_pool.swap(result.intermediate_amount, 0, false)?;
-    } else {
-        amm_pool.swap(result.amount_in, 0, true)?;
-        pumpswap_pool.swap(result.intermediate_amount, 0, false)?;
-    }
+    let execution = execute_arb(accounts, &pumpswap_pool, &amm_pool, &params, slot)?;
+    let result = &execution.result;
+
+    emit_cpi!(ArbExecuted {
+        user: ctx.accounts.common.user.key(),
+        pool_a: pumpswap_pool.get_pool_key(),
+        pool_b: amm_pool.get_pool_key(),
+        dex_a: pumpswap_pool.get_pool_kind(),
+        dex_b: amm_pool.get_pool_kind(),
+        direction_a_to_b: result.direction_a_to_b,
+        amount_in: result.amount_in,
+        intermediate_amount: result.intermediate_amount,
+        predicted_profit: result.profit,
+        realized_profit: execution.realized_profit,
+        price_delta_bps: result.price_delta_bps,
+        crosses_fee_tier: result.crosses_fee_tier,
+        iterations: result.iterations,
+        slot,
+    });
 
     Ok(())
 }
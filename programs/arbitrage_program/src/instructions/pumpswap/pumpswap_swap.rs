@@ -1,20 +1,49 @@
 use crate::arbitrage_engine::{BasePool, BaseSwapParams};
-use crate::commons::create_ata_if_missing;
+use crate::commons::{
+    check_wrap_sol_allowed, create_ata_if_missing, get_ata_balance, resolve_token_program,
+    screen_mint, unwrap_sol, wrap_sol, SwapAuthority,
+};
 use crate::dex::pumpswap::pumpswap_pool::PumpswapPool;
+use crate::events::SwapExecuted;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct PumpswapSwap<'info> {
     pub common: CommonAccounts<'info>,
     pub pumpswap: PumpswapAccounts<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub vault_signer: VaultSignerAccounts<'info>,
 }
 
 pub fn pumpswap_swap<'b, 'info>(
     ctx: Context<'_, 'b, '_, 'info, PumpswapSwap<'info>>,
     params: BaseSwapParams,
 ) -> Result<()> {
-    let pumpswap_pool = PumpswapPool::new(&ctx.accounts.common, &ctx.accounts.pumpswap)?;
+    ctx.accounts
+        .config
+        .check_access(ctx.accounts.common.user.key, IX_PUMPSWAP_SWAP)?;
+
+    if let (true, Some(screening)) = (params.source_to_intermediate, &params.mint_screening) {
+        screen_mint(&ctx.accounts.common.user_intermediate_token_mint, screening)?;
+    }
+
+    let authority = SwapAuthority::resolve(&ctx.accounts.common, &ctx.accounts.vault_signer)?;
+
+    if params.wrap_sol {
+        check_wrap_sol_allowed(&ctx.accounts.common, &authority)?;
+    }
+
+    let pumpswap_pool = PumpswapPool::new(
+        &ctx.accounts.common,
+        &ctx.accounts.pumpswap,
+        authority.clone(),
+        params.pumpswap_accounts.verifies_passed(),
+    )?;
 
     let test_result =
         pumpswap_pool.get_amount_out(params.amount_in, !params.source_to_intermediate)?;
@@ -35,18 +64,58 @@ pub fn pumpswap_swap<'b, 'info>(
 
     create_ata_if_missing(
         &ctx.accounts.common.user,
+        &authority.to_account_info(),
         &ctx.accounts.common.user_intermediate_token_account,
         &ctx.accounts.common.user_intermediate_token_mint,
         &ctx.accounts.common.system_program,
-        &ctx.accounts.common.token_program,
+        &resolve_token_program(
+            &ctx.accounts.common.user_intermediate_token_mint,
+            &ctx.accounts.common,
+        )?,
         &ctx.accounts.common.associated_token_program,
     )?;
 
+    // Покупка исполняется как buy на точный base: списывается не больше consumed_in_amount.
+    // При продаже за SOL wrap_sol только создаёт пустой WSOL ATA для выхода
+    let amount_in = test_result.consumed_in_amount;
+    if params.wrap_sol {
+        let wrap_amount = if params.source_to_intermediate {
+            amount_in
+        } else {
+            0
+        };
+        wrap_sol(&ctx.accounts.common, &authority, wrap_amount)?;
+    }
+
+    // Фактический выход - по изменению баланса выходного аккаунта до закрытия WSOL ATA
+    let destination = if params.source_to_intermediate {
+        &ctx.accounts.common.user_intermediate_token_account
+    } else {
+        &ctx.accounts.common.user_source_token_account
+    };
+    let destination_balance_before = get_ata_balance(destination)?;
+
     pumpswap_pool.swap(
-        test_result.consumed_in_amount + 1,
+        amount_in,
         test_result.amount_out,
         params.source_to_intermediate,
     )?;
 
+    let amount_out = get_ata_balance(destination)?.saturating_sub(destination_balance_before);
+
+    emit_cpi!(SwapExecuted {
+        user: ctx.accounts.common.user.key(),
+        pool: pumpswap_pool.get_pool_key(),
+        dex: pumpswap_pool.get_pool_kind(),
+        source_to_intermediate: params.source_to_intermediate,
+        amount_in,
+        min_amount_out: test_result.amount_out,
+        amount_out,
+    });
+
+    if params.wrap_sol {
+        unwrap_sol(&ctx.accounts.common)?;
+    }
+
     Ok(())
 }
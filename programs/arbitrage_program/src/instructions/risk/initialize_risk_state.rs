@@ -0,0 +1,28 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeRiskState<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = operator,
+        seeds = [RISK_STATE_SEED, operator.key().as_ref()],
+        bump,
+        space = 8 + RiskState::INIT_SPACE,
+    )]
+    pub risk_state: Account<'info, RiskState>,
+}
+
+pub fn initialize_risk_state(ctx: Context<InitializeRiskState>) -> Result<()> {
+    let risk_state = &mut ctx.accounts.risk_state;
+
+    risk_state.bump = ctx.bumps.risk_state;
+    risk_state.operator = ctx.accounts.operator.key();
+
+    Ok(())
+}
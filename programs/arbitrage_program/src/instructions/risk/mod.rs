@@ -0,0 +1,2 @@
+pub mod initialize_risk_state;
+pub use initialize_risk_state::*;
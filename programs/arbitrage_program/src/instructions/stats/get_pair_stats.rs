@@ -0,0 +1,12 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetPairStats<'info> {
+    pub pair_stats: Account<'info, PairStats>,
+}
+
+/// Возвращает статистику пары через return data (для simulateTransaction)
+pub fn get_pair_stats(ctx: Context<GetPairStats>) -> Result<PairStatsView> {
+    Ok(ctx.accounts.pair_stats.to_view())
+}
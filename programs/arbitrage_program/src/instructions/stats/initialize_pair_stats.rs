@@ -0,0 +1,50 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(per_operator: bool)]
+pub struct InitializePairStats<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    /// CHECK: используется только как ключ пары
+    pub pool_a: UncheckedAccount<'info>,
+    /// CHECK: используется только как ключ пары
+    pub pool_b: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = operator,
+        seeds = [
+            PAIR_STATS_SEED,
+            pool_a.key().as_ref(),
+            pool_b.key().as_ref(),
+            pair_stats_operator(operator.key, per_operator).as_ref(),
+        ],
+        bump,
+        space = 8 + PairStats::INIT_SPACE,
+    )]
+    pub pair_stats: Account<'info, PairStats>,
+}
+
+/// Ключ оператора в seeds: сам оператор или Pubkey::default() для общей статистики пары
+pub fn pair_stats_operator(operator: &Pubkey, per_operator: bool) -> Pubkey {
+    if per_operator {
+        *operator
+    } else {
+        Pubkey::default()
+    }
+}
+
+pub fn initialize_pair_stats(ctx: Context<InitializePairStats>, per_operator: bool) -> Result<()> {
+    let pair_stats = &mut ctx.accounts.pair_stats;
+
+    pair_stats.bump = ctx.bumps.pair_stats;
+    pair_stats.pool_a = ctx.accounts.pool_a.key();
+    pair_stats.pool_b = ctx.accounts.pool_b.key();
+    pair_stats.operator = pair_stats_operator(ctx.accounts.operator.key, per_operator);
+
+    Ok(())
+}
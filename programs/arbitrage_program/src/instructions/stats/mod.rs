@@ -0,0 +1,5 @@
+pub mod initialize_pair_stats;
+pub use initialize_pair_stats::*;
+
+pub mod get_pair_stats;
+pub use get_pair_stats::*;
@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump,
+        space = 8 + Vault::INIT_SPACE,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA без данных, владелец токен-аккаунтов vault-а
+    #[account(seeds = [VAULT_AUTHORITY_SEED, vault.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+}
+
+pub fn initialize_vault(ctx: Context<InitializeVault>, operators: Vec<Pubkey>) -> Result<()> {
+    require!(
+        operators.len() <= MAX_OPERATORS,
+        ErrorCode::TooManyOperators
+    );
+
+    let vault = &mut ctx.accounts.vault;
+
+    vault.bump = ctx.bumps.vault;
+    vault.owner = ctx.accounts.owner.key();
+    vault.authority = ctx.accounts.vault_authority.key();
+    vault.authority_bump = ctx.bumps.vault_authority;
+    vault.operators = operators;
+
+    Ok(())
+}
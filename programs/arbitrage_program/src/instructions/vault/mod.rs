@@ -0,0 +1,11 @@
+pub mod initialize_vault;
+pub use initialize_vault::*;
+
+pub mod vault_deposit;
+pub use vault_deposit::*;
+
+pub mod vault_withdraw;
+pub use vault_withdraw::*;
+
+pub mod set_vault_operators;
+pub use set_vault_operators::*;
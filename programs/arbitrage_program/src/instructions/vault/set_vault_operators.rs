@@ -0,0 +1,27 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetVaultOperators<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner,
+    )]
+    pub vault: Account<'info, Vault>,
+}
+
+pub fn set_vault_operators(ctx: Context<SetVaultOperators>, operators: Vec<Pubkey>) -> Result<()> {
+    require!(
+        operators.len() <= MAX_OPERATORS,
+        ErrorCode::TooManyOperators
+    );
+
+    ctx.accounts.vault.operators = operators;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct VaultWithdraw<'info> {
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [VAULT_SEED, owner.key().as_ref()],
+        bump = vault.bump,
+        has_one = owner,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: проверяется через vault.authority
+    #[account(address = vault.authority)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn vault_withdraw(ctx: Context<VaultWithdraw>, amount: u64) -> Result<()> {
+    let vault_key = ctx.accounts.vault.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        VAULT_AUTHORITY_SEED,
+        vault_key.as_ref(),
+        &[ctx.accounts.vault.authority_bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.owner_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+
+    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)
+}
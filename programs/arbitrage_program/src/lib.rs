@@ -5,11 +5,13 @@ pub mod arbitrage_engine;
 pub mod commons;
 pub mod dex;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
 use anchor_lang::prelude::*;
-use arbitrage_engine::{BaseSwapParams, UniversalOptimalAmountParams};
+use arbitrage_engine::{BaseSwapParams, PoolKind, UniversalOptimalAmountParams};
+use state::{InitArbitrageContextParams, InitializeConfigParams, UpdateConfigParams};
 
 // Note: Using glob import here is necessary for the #[program] macro to work correctly
 // even though it causes an "ambiguous glob re-exports" warning
@@ -20,6 +22,7 @@ declare_id!("B1111111111111111111111111111111111111111111");
 pub mod arbitrage_program {
     use super::*;
     pub use instructions::*;
+    pub use state::*;
 
     pub fn raydium_amm_swap<'b, 'info>(
         ctx: Context<'_, 'b, '_, 'info, RaydiumAmmSwap<'info>>,
@@ -42,4 +45,96 @@ pub mod arbitrage_program {
     ) -> Result<()> {
         instructions::pumpswap_amm_arb(ctx, params)
     }
+
+    pub fn two_pool_arb<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TwoPoolArb<'info>>,
+        pool_kind_a: Option<PoolKind>,
+        pool_kind_b: Option<PoolKind>,
+        params: UniversalOptimalAmountParams,
+    ) -> Result<()> {
+        instructions::two_pool_arb(ctx, pool_kind_a, pool_kind_b, params)
+    }
+
+    //-------------------------------------------------------------------
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        params: InitializeConfigParams,
+    ) -> Result<()> {
+        instructions::initialize_config(ctx, params)
+    }
+
+    pub fn update_config(
+        ctx: Context<ConfigAdminAccounts>,
+        params: UpdateConfigParams,
+    ) -> Result<()> {
+        instructions::update_config(ctx, params)
+    }
+
+    pub fn rotate_admin(ctx: Context<ConfigAdminAccounts>, new_admin: Pubkey) -> Result<()> {
+        instructions::rotate_admin(ctx, new_admin)
+    }
+
+    pub fn set_paused(ctx: Context<ConfigAdminAccounts>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    //-------------------------------------------------------------------
+
+    pub fn initialize_vault(ctx: Context<InitializeVault>, operators: Vec<Pubkey>) -> Result<()> {
+        instructions::initialize_vault(ctx, operators)
+    }
+
+    pub fn set_vault_operators(
+        ctx: Context<SetVaultOperators>,
+        operators: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_vault_operators(ctx, operators)
+    }
+
+    pub fn vault_deposit(ctx: Context<VaultDeposit>, amount: u64) -> Result<()> {
+        instructions::vault_deposit(ctx, amount)
+    }
+
+    pub fn vault_withdraw(ctx: Context<VaultWithdraw>, amount: u64) -> Result<()> {
+        instructions::vault_withdraw(ctx, amount)
+    }
+
+    pub fn initialize_risk_state(ctx: Context<InitializeRiskState>) -> Result<()> {
+        instructions::initialize_risk_state(ctx)
+    }
+
+    pub fn initialize_pair_stats(
+        ctx: Context<InitializePairStats>,
+        per_operator: bool,
+    ) -> Result<()> {
+        instructions::initialize_pair_stats(ctx, per_operator)
+    }
+
+    pub fn get_pair_stats(ctx: Context<GetPairStats>) -> Result<PairStatsView> {
+        instructions::get_pair_stats(ctx)
+    }
+
+    //-------------------------------------------------------------------
+
+    pub fn init_arbitrage_context<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitArbitrageContextAccounts<'info>>,
+        params: InitArbitrageContextParams,
+    ) -> Result<()> {
+        instructions::init_arbitrage_context_instruction(ctx, params)
+    }
+
+    pub fn verify_arbitrage_context<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyArbitrageAccounts<'info>>,
+    ) -> Result<()> {
+        instructions::verify_arbitrage_context_instruction(ctx)
+    }
+
+    pub fn migrate_arbitrage_context(ctx: Context<MigrateArbitrageContextAccounts>) -> Result<()> {
+        instructions::migrate_arbitrage_context_instruction(ctx)
+    }
+
+    pub fn close_arbitrage_context(ctx: Context<CloseArbitrageContextAccounts>) -> Result<()> {
+        instructions::close_arbitrage_context_instruction(ctx)
+    }
 }
@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::Token, token_2022::Token2022};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Token, TokenAccount},
+    token_2022::Token2022,
+};
 
-use crate::state::MEMO_PROGRAM_ID;
+use crate::dex::{is_pumpswap_fee_program, is_pumpswap_program, is_raydium_amm_v4_program};
+use crate::error::ErrorCode;
+use crate::state::*;
 
 #[derive(Accounts)]
 pub struct CommonAccounts<'info> {
@@ -48,10 +55,13 @@ pub struct CommonAccounts<'info> {
 #[derive(Accounts)]
 pub struct PumpswapAccounts<'info> {
     /// CHECK:
+    #[account(constraint = is_pumpswap_program(program_id.key) @ ErrorCode::InvalidDexProgramId)]
     pub program_id: AccountInfo<'info>,
     /// CHECK:
+    #[account(owner = program_id.key() @ ErrorCode::InvalidAccountOwner)]
     pub pool: AccountInfo<'info>,
     /// CHECK:
+    #[account(owner = program_id.key() @ ErrorCode::InvalidAccountOwner)]
     pub global_config: AccountInfo<'info>,
     /// CHECK:
     pub base_mint: AccountInfo<'info>,
@@ -82,17 +92,65 @@ pub struct PumpswapAccounts<'info> {
     #[account(mut)]
     pub user_volume_accumulator: AccountInfo<'info>,
     /// CHECK:
+    #[account(owner = fee_program.key() @ ErrorCode::InvalidAccountOwner)]
     pub fee_config: AccountInfo<'info>,
     /// CHECK:
+    #[account(constraint = is_pumpswap_fee_program(fee_program.key) @ ErrorCode::InvalidDexProgramId)]
     pub fee_program: AccountInfo<'info>,
 }
 
+impl PumpswapAccounts<'_> {
+    /// Число аккаунтов структуры - длина ноги в remaining_accounts
+    pub const LEN: usize = 16;
+}
+
+/// PumpswapAccounts без аккаунтов, которые выводятся из пула и подписанта: event_authority,
+/// creator_vault, creator, global_volume_accumulator, user_volume_accumulator, fee_config.
+/// CPI получает только переданные программе аккаунты, поэтому они всё равно идут в инструкцию,
+/// но в хвосте remaining_accounts в любом порядке и без дублей между ногами
 #[derive(Accounts)]
-pub struct RaydiumAmmAccounts<'info> {
+pub struct PumpswapSlimAccounts<'info> {
     /// CHECK:
+    #[account(constraint = is_pumpswap_program(program_id.key) @ ErrorCode::InvalidDexProgramId)]
     pub program_id: AccountInfo<'info>,
     /// CHECK:
+    #[account(owner = program_id.key() @ ErrorCode::InvalidAccountOwner)]
+    pub pool: AccountInfo<'info>,
+    /// CHECK:
+    #[account(owner = program_id.key() @ ErrorCode::InvalidAccountOwner)]
+    pub global_config: AccountInfo<'info>,
+    /// CHECK:
+    pub base_mint: AccountInfo<'info>,
+    /// CHECK:
+    pub quote_mint: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub base_token_account: AccountInfo<'info>,
+    ///CHECK:
+    #[account(mut)]
+    pub quote_token_account: AccountInfo<'info>,
+    /// CHECK:
+    pub protocol_fee_recipient: AccountInfo<'info>,
+    /// CHECK:
     #[account(mut)]
+    pub protocol_fee_recipient_token_account: AccountInfo<'info>,
+    /// CHECK:
+    #[account(constraint = is_pumpswap_fee_program(fee_program.key) @ ErrorCode::InvalidDexProgramId)]
+    pub fee_program: AccountInfo<'info>,
+}
+
+impl PumpswapSlimAccounts<'_> {
+    /// Число аккаунтов структуры - длина ноги в remaining_accounts
+    pub const LEN: usize = 10;
+}
+
+#[derive(Accounts)]
+pub struct RaydiumAmmAccounts<'info> {
+    /// CHECK:
+    #[account(constraint = is_raydium_amm_v4_program(program_id.key) @ ErrorCode::InvalidDexProgramId)]
+    pub program_id: AccountInfo<'info>,
+    /// CHECK:
+    #[account(mut, owner = program_id.key() @ ErrorCode::InvalidAccountOwner)]
     pub amm_info: AccountInfo<'info>,
     /// CHECK:
     pub amm_authority: AccountInfo<'info>,
@@ -103,3 +161,114 @@ pub struct RaydiumAmmAccounts<'info> {
     #[account(mut)]
     pub pc_vault: AccountInfo<'info>,
 }
+
+impl RaydiumAmmAccounts<'_> {
+    /// Число аккаунтов структуры - длина ноги в remaining_accounts
+    pub const LEN: usize = 5;
+}
+
+/// Аккаунты арбитражных инструкций, не зависящие от DEX ног.
+/// Constraints вложенной структуры не видят CommonAccounts, поэтому привязка risk_state и
+/// operator_token_account к подписанту проверяется в ArbAccounts::new
+#[derive(Accounts)]
+pub struct ArbSharedAccounts<'info> {
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [RISK_STATE_SEED, risk_state.operator.as_ref()],
+        bump = risk_state.bump,
+    )]
+    pub risk_state: Account<'info, RiskState>,
+
+    // Статистика пары: общая (operator = default) или конкретного оператора
+    #[account(mut)]
+    pub pair_stats: Option<Account<'info, PairStats>>,
+
+    pub vault_signer: VaultSignerAccounts<'info>,
+
+    // Получатели прибыли: адреса берутся из config.profit_split, а не из instruction data
+    /// CHECK: адрес сверяется с конфигом
+    #[account(mut, address = config.profit_split.treasury @ ErrorCode::InvalidProfitSplitAccount)]
+    pub treasury_token_account: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub operator_token_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: адрес сверяется с конфигом
+    #[account(
+        mut,
+        constraint = config.profit_split.tip_account == Some(tip_account.key()) @ ErrorCode::InvalidProfitSplitAccount,
+    )]
+    pub tip_account: Option<UncheckedAccount<'info>>,
+
+    // Чаевые валидатору в режиме params.tip: получатель задаётся вызывающим
+    /// CHECK: произвольный получатель лампортов
+    #[account(mut)]
+    pub validator_tip_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: пустой WSOL аккаунт authority, не source, проверяется в pay_tip перед закрытием
+    #[account(mut)]
+    pub tip_unwrap_token_account: Option<UncheckedAccount<'info>>,
+
+    // Нужен только в режиме flash-займа для поиска borrow/repay
+    /// CHECK:
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[cfg(test)]
+mod tests_leg_accounts_len {
+    use super::*;
+
+    #[test]
+    fn test_len_matches_fields() {
+        let key = Pubkey::new_unique;
+
+        let pumpswap = __client_accounts_pumpswap_accounts::PumpswapAccounts {
+            program_id: key(),
+            pool: key(),
+            global_config: key(),
+            base_mint: key(),
+            quote_mint: key(),
+            base_token_account: key(),
+            quote_token_account: key(),
+            protocol_fee_recipient: key(),
+            protocol_fee_recipient_token_account: key(),
+            event_authority: key(),
+            creator_vault: key(),
+            creator: key(),
+            global_volume_accumulator: key(),
+            user_volume_accumulator: key(),
+            fee_config: key(),
+            fee_program: key(),
+        };
+        let pumpswap_slim = __client_accounts_pumpswap_slim_accounts::PumpswapSlimAccounts {
+            program_id: key(),
+            pool: key(),
+            global_config: key(),
+            base_mint: key(),
+            quote_mint: key(),
+            base_token_account: key(),
+            quote_token_account: key(),
+            protocol_fee_recipient: key(),
+            protocol_fee_recipient_token_account: key(),
+            fee_program: key(),
+        };
+        let raydium_amm = __client_accounts_raydium_amm_accounts::RaydiumAmmAccounts {
+            program_id: key(),
+            amm_info: key(),
+            amm_authority: key(),
+            coin_vault: key(),
+            pc_vault: key(),
+        };
+
+        assert_eq!(pumpswap.to_account_metas(None).len(), PumpswapAccounts::LEN);
+        assert_eq!(
+            pumpswap_slim.to_account_metas(None).len(),
+            PumpswapSlimAccounts::LEN
+        );
+        assert_eq!(
+            raydium_amm.to_account_metas(None).len(),
+            RaydiumAmmAccounts::LEN
+        );
+    }
+}
@@ -1,14 +1,90 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_lang::Discriminator;
+
+// версия раскладки ArbCtx, увеличивается при изменении формата снапшота
+pub const ARB_CTX_VERSION: u8 = 1;
+
+// максимальное количество токен-аккаунтов в снапшоте
+pub const MAX_CTX_ASSETS: usize = 8;
+
+// ArbCtx до появления версии: discriminator + bump + owner + start_sol + start_src
+pub const LEGACY_ARB_CTX_LEN: usize = 8 + 1 + 32 + 8 + 8;
+pub const LEGACY_ARB_CTX_VERSION: u8 = 0;
+
+/// Правило проверки баланса токен-аккаунта при verify
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AssetRule {
+    /// Баланс не проверяется
+    Ignore,
+    /// Баланс не должен уменьшиться
+    NoDecrease,
+    /// Изменение баланса должно быть не меньше указанного (может быть отрицательным)
+    MinDelta(i64),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct AssetSnapshot {
+    pub token_account: Pubkey,
+    pub start_amount: u64,
+    pub rule: AssetRule,
+}
 
 #[account]
 #[derive(InitSpace)]
 pub struct ArbCtx {
+    pub version: u8,
     pub bump: u8,
     pub owner: Pubkey,
 
     // стартовые балансы
     pub start_sol: u64,
     pub start_src: u64,
+
+    // снапшот дополнительных токен-аккаунтов из remaining_accounts
+    #[max_len(MAX_CTX_ASSETS)]
+    pub assets: Vec<AssetSnapshot>,
+}
+
+impl ArbCtx {
+    /// Версия раскладки по сырым данным аккаунта: у старой раскладки нет поля version,
+    /// она узнаётся по длине, у новых version - первый байт после discriminator
+    pub fn layout_version(data: &[u8]) -> Option<u8> {
+        if data.len() < 8 || data[..8] != *Self::DISCRIMINATOR {
+            return None;
+        }
+
+        if data.len() == LEGACY_ARB_CTX_LEN {
+            Some(LEGACY_ARB_CTX_VERSION)
+        } else {
+            data.get(8).copied()
+        }
+    }
+
+    /// Читает контекст в старой раскладке, снапшот дополнительных аккаунтов пустой
+    pub fn from_legacy(data: &[u8]) -> Option<Self> {
+        if Self::layout_version(data)? != LEGACY_ARB_CTX_VERSION {
+            return None;
+        }
+
+        let read_u64 =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        Some(Self {
+            version: ARB_CTX_VERSION,
+            bump: data[8],
+            owner: Pubkey::new_from_array(data[9..41].try_into().unwrap()),
+            start_sol: read_u64(41),
+            start_src: read_u64(49),
+            assets: Vec::new(),
+        })
+    }
+}
+
+/// Параметры инициализации контекста: по одному правилу на каждый remaining account
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitArbitrageContextParams {
+    pub asset_rules: Vec<AssetRule>,
 }
 
 #[derive(Accounts)]
@@ -30,6 +106,44 @@ pub struct InitArbitrageContextAccounts<'info> {
         space = 8 + ArbCtx::INIT_SPACE,
     )]
     pub arb_ctx: Account<'info, ArbCtx>,
+
+    /// CHECK:
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+/// Перевод контекста старой раскладки в текущую: realloc до нового размера с доплатой ренты
+#[derive(Accounts)]
+pub struct MigrateArbitrageContextAccounts<'info> {
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: старая раскладка не десериализуется как ArbCtx, проверяется вручную
+    #[account(
+        mut,
+        seeds = [b"context", user.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub arb_ctx: AccountInfo<'info>,
+}
+
+/// Закрытие контекста любой версии с возвратом ренты пользователю
+#[derive(Accounts)]
+pub struct CloseArbitrageContextAccounts<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: закрывается без десериализации, чтобы работать и со старой раскладкой
+    #[account(
+        mut,
+        seeds = [b"context", user.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub arb_ctx: AccountInfo<'info>,
 }
 
 // объявляем константу
@@ -46,12 +160,16 @@ pub struct VerifyArbitrageAccounts<'info> {
     #[account(mut)]
     pub user_source_token_account: AccountInfo<'info>,
 
-    // #[account(
-    //     mut,
-    //     close = user, // вернём ренту пользователю
-    //     seeds = [b"context", user.key().as_ref()],
-    //     bump = arb_ctx.bump,
-    //     constraint = arb_ctx.owner == user.key(),
-    // )]
+    #[account(
+        // mut,
+        // close = user, // вернём ренту пользователю
+        seeds = [b"context", user.key().as_ref()],
+        bump = arb_ctx.bump,
+        constraint = arb_ctx.owner == user.key(),
+    )]
     pub arb_ctx: Account<'info, ArbCtx>,
+
+    /// CHECK:
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
 }
@@ -0,0 +1,255 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state::RiskLimits;
+
+pub const CONFIG_SEED: &[u8] = b"config";
+
+// максимальное количество операторов в allowlist
+pub const MAX_OPERATORS: usize = 16;
+
+// биты Config.enabled_instructions
+pub const IX_PUMPSWAP_AMM_ARB: u32 = 1 << 0;
+pub const IX_PUMPSWAP_SWAP: u32 = 1 << 1;
+pub const IX_RAYDIUM_AMM_SWAP: u32 = 1 << 2;
+pub const IX_TWO_POOL_ARB: u32 = 1 << 3;
+
+// знаменатель долей ProfitSplit
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Распределение реализованной прибыли после арбитража
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct ProfitSplit {
+    /// Токен-аккаунт казначейства (в минте source токена)
+    pub treasury: Pubkey,
+    /// Доля прибыли в казначейство, bps
+    pub treasury_bps: u16,
+    /// Доля прибыли оператору, bps
+    pub operator_bps: u16,
+    /// Аккаунт для чаевых в лампортах, None - чаевые не платятся
+    pub tip_account: Option<Pubkey>,
+    pub tip_lamports: u64,
+}
+
+impl ProfitSplit {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.treasury_bps as u64 + self.operator_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidProfitSplit
+        );
+
+        Ok(())
+    }
+
+    /// Возвращает (доля казначейства, доля оператора) от прибыли
+    pub fn shares(&self, profit: u64) -> (u64, u64) {
+        let share = |bps: u16| (profit as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+        (share(self.treasury_bps), share(self.operator_bps))
+    }
+
+    /// Чаевые tip_lamports из прибыли: (чаевые, остаток прибыли для долей).
+    /// Чаевые в лампортах сравнимы с прибылью только для SOL source, и не больше прибыли
+    pub fn split_tip(&self, profit: u64, is_native_source: bool) -> Result<(u64, u64)> {
+        if self.tip_account.is_none() || self.tip_lamports == 0 || profit == 0 {
+            return Ok((0, profit));
+        }
+
+        require!(is_native_source, ErrorCode::TipRequiresNativeSource);
+
+        let tip = self.tip_lamports.min(profit);
+
+        Ok((tip, profit - tip))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.treasury_bps > 0 || self.operator_bps > 0 || self.tip_account.is_some()
+    }
+}
+
+/// Глобальный конфиг программы: админ, операторы, пауза и включённые инструкции
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub enabled_instructions: u32,
+
+    #[max_len(MAX_OPERATORS)]
+    pub operators: Vec<Pubkey>,
+
+    pub profit_split: ProfitSplit,
+    pub risk_limits: RiskLimits,
+
+    /// Минимальная комиссия flash-займа, bps: FlashLoanParams.fee_bps вызывающего не может быть ниже,
+    /// иначе realized_profit в risk_state, pair_stats и событиях завышен
+    pub min_flash_loan_fee_bps: u16,
+}
+
+impl Config {
+    /// Проверяет, что программа не на паузе, инструкция включена и подписант в allowlist
+    pub fn check_access(&self, operator: &Pubkey, instruction_flag: u32) -> Result<()> {
+        require!(!self.paused, ErrorCode::ProgramPaused);
+        require!(
+            self.enabled_instructions & instruction_flag != 0,
+            ErrorCode::InstructionDisabled
+        );
+        require!(
+            self.operators.contains(operator),
+            ErrorCode::UnauthorizedOperator
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitializeConfigParams {
+    pub enabled_instructions: u32,
+    pub operators: Vec<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateConfigParams {
+    pub enabled_instructions: Option<u32>,
+    pub operators: Option<Vec<Pubkey>>,
+    pub profit_split: Option<ProfitSplit>,
+    pub risk_limits: Option<RiskLimits>,
+    pub min_flash_loan_fee_bps: Option<u16>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigAdminAccounts<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[cfg(test)]
+mod tests_config_check_access {
+    use super::*;
+
+    fn config(operator: Pubkey) -> Config {
+        Config {
+            bump: 255,
+            admin: Pubkey::new_unique(),
+            paused: false,
+            enabled_instructions: IX_PUMPSWAP_AMM_ARB | IX_RAYDIUM_AMM_SWAP,
+            operators: vec![operator],
+            profit_split: ProfitSplit::default(),
+            risk_limits: RiskLimits::default(),
+            min_flash_loan_fee_bps: 0,
+        }
+    }
+
+    #[test]
+    fn test_allowed() {
+        let operator = Pubkey::new_unique();
+        let config = config(operator);
+
+        assert!(config.check_access(&operator, IX_PUMPSWAP_AMM_ARB).is_ok());
+        assert!(config.check_access(&operator, IX_RAYDIUM_AMM_SWAP).is_ok());
+    }
+
+    #[test]
+    fn test_rejected() {
+        let operator = Pubkey::new_unique();
+        let mut config = config(operator);
+
+        assert_eq!(
+            config
+                .check_access(&operator, IX_PUMPSWAP_SWAP)
+                .unwrap_err(),
+            ErrorCode::InstructionDisabled.into()
+        );
+        assert_eq!(
+            config
+                .check_access(&Pubkey::new_unique(), IX_PUMPSWAP_AMM_ARB)
+                .unwrap_err(),
+            ErrorCode::UnauthorizedOperator.into()
+        );
+
+        config.paused = true;
+        assert_eq!(
+            config
+                .check_access(&operator, IX_PUMPSWAP_AMM_ARB)
+                .unwrap_err(),
+            ErrorCode::ProgramPaused.into()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_profit_split {
+    use super::*;
+
+    fn split(treasury_bps: u16, operator_bps: u16) -> ProfitSplit {
+        ProfitSplit {
+            treasury_bps,
+            operator_bps,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_shares() {
+        assert_eq!(split(2_000, 500).shares(1_000_000), (200_000, 50_000));
+        assert_eq!(split(2_000, 500).shares(3), (0, 0));
+        assert_eq!(split(10_000, 0).shares(u64::MAX), (u64::MAX, 0));
+        assert_eq!(split(0, 0).shares(1_000), (0, 0));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(split(5_000, 5_000).validate().is_ok());
+        assert_eq!(
+            split(5_000, 5_001).validate().unwrap_err(),
+            ErrorCode::InvalidProfitSplit.into()
+        );
+    }
+
+    #[test]
+    fn test_split_tip() {
+        let with_tip = ProfitSplit {
+            tip_account: Some(Pubkey::new_unique()),
+            tip_lamports: 10_000,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            with_tip.split_tip(1_000_000, true).unwrap(),
+            (10_000, 990_000)
+        );
+        // не больше прибыли и ничего без прибыли
+        assert_eq!(with_tip.split_tip(4_000, true).unwrap(), (4_000, 0));
+        assert_eq!(with_tip.split_tip(0, true).unwrap(), (0, 0));
+        assert_eq!(
+            with_tip.split_tip(1_000_000, false).unwrap_err(),
+            ErrorCode::TipRequiresNativeSource.into()
+        );
+
+        // без tip_account прибыль не меняется для любого source
+        assert_eq!(split(1, 0).split_tip(1_000, false).unwrap(), (0, 1_000));
+    }
+
+    #[test]
+    fn test_is_enabled() {
+        assert!(!ProfitSplit::default().is_enabled());
+        assert!(split(1, 0).is_enabled());
+
+        let tip_only = ProfitSplit {
+            tip_account: Some(Pubkey::new_unique()),
+            ..Default::default()
+        };
+        assert!(tip_only.is_enabled());
+    }
+}
@@ -3,3 +3,15 @@ pub use arb_accounts::*;
 
 pub mod arb_context_accounts;
 pub use arb_context_accounts::*;
+
+pub mod config_accounts;
+pub use config_accounts::*;
+
+pub mod vault_accounts;
+pub use vault_accounts::*;
+
+pub mod risk_accounts;
+pub use risk_accounts::*;
+
+pub mod pair_stats_accounts;
+pub use pair_stats_accounts::*;
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+pub const PAIR_STATS_SEED: &[u8] = b"pair_stats";
+
+// Размер кольцевого буфера последних прибылей
+pub const PAIR_STATS_HISTORY_LEN: usize = 16;
+
+/// Статистика успешных арбитражей по паре пулов.
+/// PDA: [PAIR_STATS_SEED, pool_a, pool_b, operator], operator = Pubkey::default() - по всем операторам
+#[account]
+#[derive(InitSpace)]
+pub struct PairStats {
+    pub bump: u8,
+    pub pool_a: Pubkey,
+    pub pool_b: Pubkey,
+    pub operator: Pubkey,
+
+    pub execution_count: u64,
+    pub cumulative_volume: u128,
+    pub cumulative_profit: i128,
+    pub last_slot: u64,
+
+    pub recent_profits: [i64; PAIR_STATS_HISTORY_LEN],
+    // Индекс следующей записи в recent_profits
+    pub recent_head: u8,
+}
+
+/// Ответ get_pair_stats: recent_profits от старых к новым
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PairStatsView {
+    pub pool_a: Pubkey,
+    pub pool_b: Pubkey,
+    pub operator: Pubkey,
+    pub execution_count: u64,
+    pub cumulative_volume: u128,
+    pub cumulative_profit: i128,
+    pub last_slot: u64,
+    pub recent_profits: Vec<i64>,
+}
+
+impl PairStats {
+    /// Аккаунт относится к паре pool_a/pool_b и к оператору (или общий для всех)
+    pub fn check_pair(&self, pool_a: &Pubkey, pool_b: &Pubkey, operator: &Pubkey) -> Result<()> {
+        require!(
+            self.pool_a == *pool_a
+                && self.pool_b == *pool_b
+                && (self.operator == Pubkey::default() || self.operator == *operator),
+            ErrorCode::InvalidPairStatsAccount
+        );
+
+        Ok(())
+    }
+
+    /// Учитывает успешную сделку
+    pub fn record(&mut self, slot: u64, amount_in: u64, profit: i64) {
+        self.execution_count = self.execution_count.saturating_add(1);
+        self.cumulative_volume = self.cumulative_volume.saturating_add(amount_in as u128);
+        self.cumulative_profit = self.cumulative_profit.saturating_add(profit as i128);
+        self.last_slot = slot;
+
+        self.recent_profits[self.recent_head as usize] = profit;
+        self.recent_head = ((self.recent_head as usize + 1) % PAIR_STATS_HISTORY_LEN) as u8;
+    }
+
+    /// Последние прибыли от старых к новым
+    pub fn recent_profits(&self) -> Vec<i64> {
+        let filled = (self.execution_count as usize).min(PAIR_STATS_HISTORY_LEN);
+        let start =
+            (self.recent_head as usize + PAIR_STATS_HISTORY_LEN - filled) % PAIR_STATS_HISTORY_LEN;
+
+        (0..filled)
+            .map(|i| self.recent_profits[(start + i) % PAIR_STATS_HISTORY_LEN])
+            .collect()
+    }
+
+    pub fn to_view(&self) -> PairStatsView {
+        PairStatsView {
+            pool_a: self.pool_a,
+            pool_b: self.pool_b,
+            operator: self.operator,
+            execution_count: self.execution_count,
+            cumulative_volume: self.cumulative_volume,
+            cumulative_profit: self.cumulative_profit,
+            last_slot: self.last_slot,
+            recent_profits: self.recent_profits(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_pair_stats {
+    use super::*;
+
+    fn stats() -> PairStats {
+        PairStats {
+            bump: 255,
+            pool_a: Pubkey::new_unique(),
+            pool_b: Pubkey::new_unique(),
+            operator: Pubkey::default(),
+            execution_count: 0,
+            cumulative_volume: 0,
+            cumulative_profit: 0,
+            last_slot: 0,
+            recent_profits: [0; PAIR_STATS_HISTORY_LEN],
+            recent_head: 0,
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut stats = stats();
+        assert!(stats.recent_profits().is_empty());
+
+        stats.record(10, 1_000, 50);
+        stats.record(12, 2_000, -20);
+
+        assert_eq!(stats.execution_count, 2);
+        assert_eq!(stats.cumulative_volume, 3_000);
+        assert_eq!(stats.cumulative_profit, 30);
+        assert_eq!(stats.last_slot, 12);
+        assert_eq!(stats.recent_profits(), vec![50, -20]);
+    }
+
+    #[test]
+    fn test_check_pair() {
+        let mut stats = stats();
+        let (pool_a, pool_b) = (stats.pool_a, stats.pool_b);
+        let operator = Pubkey::new_unique();
+
+        assert!(stats.check_pair(&pool_a, &pool_b, &operator).is_ok());
+        assert_eq!(
+            stats.check_pair(&pool_b, &pool_a, &operator).unwrap_err(),
+            ErrorCode::InvalidPairStatsAccount.into()
+        );
+
+        stats.operator = operator;
+        assert!(stats.check_pair(&pool_a, &pool_b, &operator).is_ok());
+        assert_eq!(
+            stats
+                .check_pair(&pool_a, &pool_b, &Pubkey::new_unique())
+                .unwrap_err(),
+            ErrorCode::InvalidPairStatsAccount.into()
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_wraps() {
+        let mut stats = stats();
+
+        let total = PAIR_STATS_HISTORY_LEN as i64 + 3;
+        for profit in 0..total {
+            stats.record(profit as u64, 1, profit);
+        }
+
+        // остаются только последние PAIR_STATS_HISTORY_LEN записей
+        let expected = (3..total).collect::<Vec<_>>();
+        assert_eq!(stats.recent_profits(), expected);
+        assert_eq!(stats.cumulative_profit, (0..total).sum::<i64>() as i128);
+    }
+}
@@ -0,0 +1,333 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+pub const RISK_STATE_SEED: &[u8] = b"risk";
+
+/// Лимиты риска на оператора, задаются админом в Config. Ноль отключает соответствующий лимит.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct RiskLimits {
+    /// Максимальный вход одной сделки
+    pub max_input_per_trade: u64,
+    /// Максимальный суммарный вход за окно
+    pub max_notional_per_window: u64,
+    /// Максимальный суммарный убыток за окно
+    pub max_cumulative_loss: u64,
+    /// Длина скользящего окна в слотах
+    pub window_slots: u64,
+    /// Количество убыточных сделок за окно, после которого включается cool-down.
+    /// Проваленная проверка прибыли откатывает транзакцию и не оставляет следа on-chain,
+    /// поэтому считаются только исполненные сделки с отрицательным P&L
+    pub max_failed_verifications: u32,
+    /// Длительность cool-down в слотах
+    pub cooldown_slots: u64,
+}
+
+// Скользящее окно делится на RISK_WINDOW_BUCKETS корзин, ещё одна - под неполную корзину на его краю
+pub const RISK_WINDOW_BUCKETS: usize = 8;
+pub const RISK_BUCKET_COUNT: usize = RISK_WINDOW_BUCKETS + 1;
+
+impl RiskLimits {
+    /// Длина корзины скользящего окна в слотах
+    pub fn bucket_slots(&self) -> u64 {
+        self.window_slots
+            .div_ceil(RISK_WINDOW_BUCKETS as u64)
+            .max(1)
+    }
+}
+
+/// Сделки оператора за bucket_slots слотов начиная с start_slot
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct RiskBucket {
+    pub start_slot: u64,
+    pub notional: u64,
+    pub realized_pnl: i64,
+    pub loss_count: u32,
+}
+
+impl RiskBucket {
+    pub fn cumulative_loss(&self) -> u64 {
+        if self.realized_pnl < 0 {
+            self.realized_pnl.unsigned_abs()
+        } else {
+            0
+        }
+    }
+}
+
+/// Состояние риска оператора: кольцо корзин по слотам для скользящего окна
+#[account]
+#[derive(InitSpace)]
+pub struct RiskState {
+    pub bump: u8,
+    pub operator: Pubkey,
+
+    pub buckets: [RiskBucket; RISK_BUCKET_COUNT],
+
+    pub cooldown_until_slot: u64,
+}
+
+impl RiskState {
+    /// Суммы по корзинам, пересекающимся с последними window_slots слотами (slot - window_slots, slot].
+    /// Сделка учитывается от window_slots до window_slots + bucket_slots слотов, но не меньше,
+    /// поэтому на границе окна нельзя набрать двойной лимит
+    pub fn window_totals(&self, limits: &RiskLimits, slot: u64) -> RiskBucket {
+        let bucket_slots = limits.bucket_slots();
+        let window_start = slot.saturating_sub(limits.window_slots);
+
+        self.buckets
+            .iter()
+            .filter(|bucket| {
+                bucket.start_slot <= slot
+                    && bucket.start_slot.saturating_add(bucket_slots) > window_start
+            })
+            .fold(RiskBucket::default(), |total, bucket| RiskBucket {
+                start_slot: total.start_slot,
+                notional: total.notional.saturating_add(bucket.notional),
+                realized_pnl: total.realized_pnl.saturating_add(bucket.realized_pnl),
+                loss_count: total.loss_count.saturating_add(bucket.loss_count),
+            })
+    }
+
+    /// Корзина слота; устаревшая корзина на её месте в кольце сбрасывается.
+    /// Корзин в кольце на одну больше, чем частей окна, поэтому сбрасываются только вышедшие из окна.
+    /// После смены window_slots учёт до конца старого окна приблизительный
+    fn current_bucket(&mut self, limits: &RiskLimits, slot: u64) -> &mut RiskBucket {
+        let bucket_slots = limits.bucket_slots();
+        let start_slot = slot - slot % bucket_slots;
+        let index = (start_slot / bucket_slots % RISK_BUCKET_COUNT as u64) as usize;
+
+        let bucket = &mut self.buckets[index];
+        if bucket.start_slot != start_slot {
+            *bucket = RiskBucket {
+                start_slot,
+                ..Default::default()
+            };
+        }
+
+        bucket
+    }
+
+    /// Проверки до сделки, возвращает максимально допустимый вход
+    pub fn check_pre_trade(&self, limits: &RiskLimits, slot: u64) -> Result<u64> {
+        require!(
+            slot >= self.cooldown_until_slot,
+            ErrorCode::RiskCooldownActive
+        );
+
+        let totals = self.window_totals(limits, slot);
+
+        if limits.max_cumulative_loss > 0 {
+            require!(
+                totals.cumulative_loss() < limits.max_cumulative_loss,
+                ErrorCode::RiskLossLimitExceeded
+            );
+        }
+
+        let mut max_amount_in = u64::MAX;
+
+        if limits.max_input_per_trade > 0 {
+            max_amount_in = max_amount_in.min(limits.max_input_per_trade);
+        }
+
+        if limits.max_notional_per_window > 0 {
+            let remaining = limits
+                .max_notional_per_window
+                .saturating_sub(totals.notional);
+            require!(remaining > 0, ErrorCode::RiskNotionalLimitExceeded);
+            max_amount_in = max_amount_in.min(remaining);
+        }
+
+        Ok(max_amount_in)
+    }
+
+    /// Учитывает сделку; убыточная сделка считается проваленной проверкой.
+    /// Ничего не отклоняет: ошибка откатила бы транзакцию вместе с учётом убытка и cool-down,
+    /// поэтому пробитые лимиты сохраняются здесь, а следующие сделки отклоняет check_pre_trade
+    pub fn record_trade(&mut self, limits: &RiskLimits, slot: u64, amount_in: u64, pnl: i64) {
+        let bucket = self.current_bucket(limits, slot);
+        bucket.notional = bucket.notional.saturating_add(amount_in);
+        bucket.realized_pnl = bucket.realized_pnl.saturating_add(pnl);
+
+        if pnl >= 0 {
+            return;
+        }
+
+        bucket.loss_count += 1;
+
+        let totals = self.window_totals(limits, slot);
+        let failed_limit_hit = limits.max_failed_verifications > 0
+            && totals.loss_count >= limits.max_failed_verifications;
+        let loss_limit_hit = limits.max_cumulative_loss > 0
+            && totals.cumulative_loss() >= limits.max_cumulative_loss;
+
+        if failed_limit_hit || loss_limit_hit {
+            self.cooldown_until_slot = slot.saturating_add(limits.cooldown_slots);
+            for bucket in self.buckets.iter_mut() {
+                bucket.loss_count = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_risk_state {
+    use super::*;
+
+    fn limits() -> RiskLimits {
+        RiskLimits {
+            max_input_per_trade: 1_000,
+            max_notional_per_window: 2_500,
+            max_cumulative_loss: 100,
+            window_slots: 50,
+            max_failed_verifications: 2,
+            cooldown_slots: 10,
+        }
+    }
+
+    fn state() -> RiskState {
+        RiskState {
+            bump: 255,
+            operator: Pubkey::new_unique(),
+            buckets: [RiskBucket::default(); RISK_BUCKET_COUNT],
+            cooldown_until_slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_max_input() {
+        let limits = limits();
+        let mut state = state();
+
+        assert_eq!(state.check_pre_trade(&limits, 1).unwrap(), 1_000);
+
+        state.record_trade(&limits, 1, 1_000, 5);
+        state.record_trade(&limits, 2, 1_000, 5);
+        // остаток бюджета окна меньше лимита на сделку
+        assert_eq!(state.check_pre_trade(&limits, 3).unwrap(), 500);
+
+        state.record_trade(&limits, 3, 500, 5);
+        assert_eq!(
+            state.check_pre_trade(&limits, 4).unwrap_err(),
+            ErrorCode::RiskNotionalLimitExceeded.into()
+        );
+
+        // корзина [0, 7) выходит из окна (slot - 50, slot] только на слоте 57
+        assert_eq!(
+            state.check_pre_trade(&limits, 56).unwrap_err(),
+            ErrorCode::RiskNotionalLimitExceeded.into()
+        );
+        assert_eq!(state.check_pre_trade(&limits, 57).unwrap(), 1_000);
+        assert_eq!(state.window_totals(&limits, 57).notional, 0);
+    }
+
+    #[test]
+    fn test_rolling_window_boundary() {
+        let limits = RiskLimits {
+            max_input_per_trade: 0,
+            ..limits()
+        };
+        let mut state = state();
+
+        // весь бюджет в конце окна: на месте старой границы окна бюджет не обновляется
+        state.record_trade(&limits, 45, 2_500, 5);
+        for slot in [50, 60, 94] {
+            assert_eq!(
+                state.check_pre_trade(&limits, slot).unwrap_err(),
+                ErrorCode::RiskNotionalLimitExceeded.into()
+            );
+        }
+        // сделка учитывается не меньше window_slots слотов
+        assert_eq!(state.check_pre_trade(&limits, 99).unwrap(), 2_500);
+
+        // частичный выход из окна освобождает только старые корзины
+        state.record_trade(&limits, 100, 1_000, 5);
+        state.record_trade(&limits, 130, 1_000, 5);
+        assert_eq!(state.check_pre_trade(&limits, 140).unwrap(), 500);
+        assert_eq!(state.check_pre_trade(&limits, 161).unwrap(), 1_500);
+    }
+
+    #[test]
+    fn test_disabled_limits() {
+        let state = state();
+
+        assert_eq!(
+            state.check_pre_trade(&RiskLimits::default(), 1).unwrap(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn test_loss_limit() {
+        let limits = RiskLimits {
+            max_failed_verifications: 0,
+            ..limits()
+        };
+        let mut state = state();
+
+        state.record_trade(&limits, 1, 100, -60);
+        assert!(state.check_pre_trade(&limits, 2).is_ok());
+
+        let mut state = self::state();
+        state.record_trade(&limits, 1, 100, -100);
+        assert_eq!(state.cooldown_until_slot, 11);
+        assert_eq!(
+            state.check_pre_trade(&limits, 11).unwrap_err(),
+            ErrorCode::RiskLossLimitExceeded.into()
+        );
+    }
+
+    #[test]
+    fn test_loss_limit_breach_persisted() {
+        let limits = RiskLimits {
+            max_failed_verifications: 0,
+            ..limits()
+        };
+        let mut state = state();
+
+        state.record_trade(&limits, 1, 100, -60);
+        // сделка, пробившая лимит, учитывается целиком и включает cool-down
+        state.record_trade(&limits, 2, 100, -41);
+        let totals = state.window_totals(&limits, 2);
+        assert_eq!(totals.realized_pnl, -101);
+        assert_eq!(totals.notional, 200);
+        assert_eq!(state.cooldown_until_slot, 12);
+
+        assert_eq!(
+            state.check_pre_trade(&limits, 3).unwrap_err(),
+            ErrorCode::RiskCooldownActive.into()
+        );
+        // после cool-down окно ещё не закончилось, и лимит убытка продолжает действовать
+        assert_eq!(
+            state.check_pre_trade(&limits, 12).unwrap_err(),
+            ErrorCode::RiskLossLimitExceeded.into()
+        );
+
+        assert_eq!(
+            state.check_pre_trade(&limits, 56).unwrap_err(),
+            ErrorCode::RiskLossLimitExceeded.into()
+        );
+        assert!(state.check_pre_trade(&limits, 57).is_ok());
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let limits = limits();
+        let mut state = state();
+
+        state.record_trade(&limits, 1, 100, -1);
+        assert!(state.check_pre_trade(&limits, 2).is_ok());
+
+        state.record_trade(&limits, 2, 100, -1);
+        assert_eq!(state.cooldown_until_slot, 12);
+        assert_eq!(
+            state.check_pre_trade(&limits, 11).unwrap_err(),
+            ErrorCode::RiskCooldownActive.into()
+        );
+        assert!(state.check_pre_trade(&limits, 12).is_ok());
+    }
+}
@@ -0,0 +1,28 @@
+use crate::state::MAX_OPERATORS;
+use anchor_lang::prelude::*;
+
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+
+/// Vault владельца капитала. Токены лежат на ATA vault_authority - PDA без данных,
+/// который подписывает CPI в DEX, поэтому ключи операторов не имеют доступа к средствам.
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub authority: Pubkey,
+    pub authority_bump: u8,
+
+    /// Операторы, которым владелец разрешил торговать капиталом vault-а
+    #[max_len(MAX_OPERATORS)]
+    pub operators: Vec<Pubkey>,
+}
+
+/// Режим vault торговых инструкций: если переданы, CPI подписывает vault_authority вместо user
+#[derive(Accounts)]
+pub struct VaultSignerAccounts<'info> {
+    pub vault: Option<Account<'info, Vault>>,
+    /// CHECK: проверяется через vault.authority
+    pub vault_authority: Option<UncheckedAccount<'info>>,
+}
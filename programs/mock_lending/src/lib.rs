@@ -0,0 +1,178 @@
+// IDL-инструкции, которые #[program] из anchor 0.31 генерирует в корне крейта, вызывают
+// устаревший AccountInfo::realloc, поэтому allow не сужается до модуля программы
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("EDZeKHZy8X5HGnXqwR7JnLV7M92UJ6FGPyoTkhfKo9a8");
+
+// Локальный мок flash-займа для тестов без mainnet.
+// Имена инструкций, аргументы и позиции первых 7 аккаунтов совпадают с Kamino Lending,
+// поэтому arbitrage_program находит borrow/repay так же, как в реальной транзакции.
+
+pub const LENDING_MARKET_AUTHORITY_SEED: &[u8] = b"lma";
+
+// комиссия займа, bps от суммы
+pub const FLASH_LOAN_FEE_BPS: u64 = 9;
+
+#[program]
+pub mod mock_lending {
+    use super::*;
+
+    pub fn flash_borrow_reserve_liquidity(
+        ctx: Context<FlashBorrowReserveLiquidity>,
+        liquidity_amount: u64,
+    ) -> Result<()> {
+        let current_index = load_current_index_checked(&ctx.accounts.sysvar_info)?;
+
+        // Как в Kamino: в транзакции должен быть repay на ту же сумму, ссылающийся на этот borrow
+        let mut index = current_index as usize + 1;
+        let mut repay_found = false;
+        while let Ok(ix) = load_instruction_at_checked(index, &ctx.accounts.sysvar_info) {
+            index += 1;
+
+            if ix.program_id != crate::ID
+                || !ix
+                    .data
+                    .starts_with(instruction::FlashRepayReserveLiquidity::DISCRIMINATOR)
+            {
+                continue;
+            }
+
+            let repay = instruction::FlashRepayReserveLiquidity::try_from_slice(&ix.data[8..])?;
+            require!(
+                repay.liquidity_amount == liquidity_amount
+                    && repay.borrow_instruction_index as u16 == current_index,
+                MockLendingError::InvalidFlashRepay
+            );
+            repay_found = true;
+            break;
+        }
+        require!(repay_found, MockLendingError::NoFlashRepayFound);
+
+        let lending_market = ctx.accounts.lending_market.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            LENDING_MARKET_AUTHORITY_SEED,
+            lending_market.as_ref(),
+            &[ctx.bumps.lending_market_authority],
+        ]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reserve_source_liquidity.to_account_info(),
+                to: ctx.accounts.user_destination_liquidity.to_account_info(),
+                authority: ctx.accounts.lending_market_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, liquidity_amount)
+    }
+
+    pub fn flash_repay_reserve_liquidity(
+        ctx: Context<FlashRepayReserveLiquidity>,
+        liquidity_amount: u64,
+        borrow_instruction_index: u8,
+    ) -> Result<()> {
+        let borrow_ix = load_instruction_at_checked(
+            borrow_instruction_index as usize,
+            &ctx.accounts.sysvar_info,
+        )?;
+        require!(
+            borrow_ix.program_id == crate::ID
+                && borrow_ix
+                    .data
+                    .starts_with(instruction::FlashBorrowReserveLiquidity::DISCRIMINATOR),
+            MockLendingError::InvalidFlashRepay
+        );
+
+        let fee = flash_loan_fee(liquidity_amount);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_source_liquidity.to_account_info(),
+                to: ctx.accounts.reserve_destination_liquidity.to_account_info(),
+                authority: ctx.accounts.user_transfer_authority.to_account_info(),
+            },
+        );
+
+        transfer(cpi_ctx, liquidity_amount + fee)
+    }
+}
+
+/// Комиссия округляется вверх
+pub fn flash_loan_fee(amount: u64) -> u64 {
+    ((amount as u128 * FLASH_LOAN_FEE_BPS as u128).div_ceil(10_000)) as u64
+}
+
+#[derive(Accounts)]
+pub struct FlashBorrowReserveLiquidity<'info> {
+    pub user_transfer_authority: Signer<'info>,
+
+    /// CHECK: PDA, владелец ликвидности резерва
+    #[account(seeds = [LENDING_MARKET_AUTHORITY_SEED, lending_market.key().as_ref()], bump)]
+    pub lending_market_authority: UncheckedAccount<'info>,
+    /// CHECK: в моке используется только как seed
+    pub lending_market: UncheckedAccount<'info>,
+    /// CHECK: в моке не используется, оставлен для совместимости раскладки
+    pub reserve: UncheckedAccount<'info>,
+    pub reserve_liquidity_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = reserve_liquidity_mint,
+        token::authority = lending_market_authority,
+    )]
+    pub reserve_source_liquidity: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = reserve_liquidity_mint)]
+    pub user_destination_liquidity: Account<'info, TokenAccount>,
+
+    /// CHECK:
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_info: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FlashRepayReserveLiquidity<'info> {
+    pub user_transfer_authority: Signer<'info>,
+
+    /// CHECK: PDA, владелец ликвидности резерва
+    #[account(seeds = [LENDING_MARKET_AUTHORITY_SEED, lending_market.key().as_ref()], bump)]
+    pub lending_market_authority: UncheckedAccount<'info>,
+    /// CHECK: в моке используется только как seed
+    pub lending_market: UncheckedAccount<'info>,
+    /// CHECK: в моке не используется, оставлен для совместимости раскладки
+    pub reserve: UncheckedAccount<'info>,
+    pub reserve_liquidity_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = reserve_liquidity_mint,
+        token::authority = lending_market_authority,
+    )]
+    pub reserve_destination_liquidity: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = reserve_liquidity_mint)]
+    pub user_source_liquidity: Account<'info, TokenAccount>,
+
+    /// CHECK:
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_info: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[error_code]
+pub enum MockLendingError {
+    #[msg("No flash repay found for this borrow")]
+    NoFlashRepayFound,
+
+    #[msg("Flash repay does not match borrow")]
+    InvalidFlashRepay,
+}